@@ -0,0 +1,41 @@
+use crate::grid::Grid;
+use crate::my_rng::Rng;
+use crate::seed_colors::SeedColors;
+use crate::state::State;
+
+/// what color seeds painted with the mouse are
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrushColor {
+    /// like `State::random_filled`
+    Random,
+    /// the same color for every seed, like one picked from the dish
+    Fixed(u8, u8, u8),
+    /// from the seed colors, like the pixel of the seed colors image under the seed
+    Sampled,
+}
+
+impl BrushColor {
+    pub fn state(self, rng: &mut Rng, grid: &Grid, seed_colors: &SeedColors, cell: usize) -> State {
+        match self {
+            Self::Random => State::random_filled(rng),
+            Self::Fixed(r, g, b) => State::Filled { r, g, b },
+            Self::Sampled => seed_colors.colors(rng, grid, &[cell])[0],
+        }
+    }
+}
+
+/// the points `spacing` apart going from `from` toward `to`, not including `from`,
+/// and the last point, which is where the stroke continues from
+pub fn stroke(from: (f32, f32), to: (f32, f32), spacing: f32) -> (Vec<(f32, f32)>, (f32, f32)) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let len = dx.hypot(dy);
+    let count = (len / spacing) as usize;
+    let points = (1..=count)
+        .map(|i| {
+            let t = i as f32 * spacing / len;
+            (dx.mul_add(t, from.0), dy.mul_add(t, from.1))
+        })
+        .collect::<Vec<_>>();
+    let last = points.last().copied().unwrap_or(from);
+    (points, last)
+}
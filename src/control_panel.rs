@@ -0,0 +1,296 @@
+use std::ffi::CString;
+
+use raylib::prelude::*;
+
+use crate::brush::BrushColor;
+use crate::seed_colors::SeedColors;
+use crate::seed_layout::SeedLayout;
+use crate::settings::Settings;
+
+/// width in pixels of the panel on the right of the window
+pub const PANEL_WIDTH: f32 = 280.0;
+const ROW_HEIGHT: f32 = 28.0;
+const MARGIN: f32 = 10.0;
+/// width of the labels left of sliders and combo boxes
+const LABEL_WIDTH: f32 = 90.0;
+/// height in pixels of the timeline along the bottom of the window
+pub const TIMELINE_HEIGHT: f32 = ROW_HEIGHT + MARGIN;
+
+/// what the buttons of the panel ask for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanelAction {
+    Reset,
+    ExportImage,
+    SaveDish,
+    LoadDish,
+}
+
+/// lays out the controls in rows from the top of the panel down
+struct Layout {
+    x: f32,
+    y: f32,
+}
+
+impl Layout {
+    fn row(&mut self) -> Rectangle {
+        let row = Rectangle::new(
+            self.x + MARGIN,
+            self.y,
+            2.0f32.mul_add(-MARGIN, PANEL_WIDTH),
+            ROW_HEIGHT - 6.0,
+        );
+        self.y += ROW_HEIGHT;
+        row
+    }
+
+    /// a row with room for a label on the left and the value on the right
+    fn labeled_row(&mut self) -> Rectangle {
+        let row = self.row();
+        Rectangle::new(
+            row.x + LABEL_WIDTH,
+            row.y,
+            row.width - LABEL_WIDTH - 50.0,
+            row.height,
+        )
+    }
+
+    /// a row with the label drawn on the left, returning where the control goes
+    fn label_row(&mut self, draw_handle: &mut RaylibDrawHandle, label: &str) -> Rectangle {
+        let row = self.labeled_row();
+        draw_handle.gui_label(
+            Rectangle::new(row.x - LABEL_WIDTH, row.y, LABEL_WIDTH, row.height),
+            Some(&text(label)),
+        );
+        row
+    }
+
+    fn checkbox(&mut self) -> Rectangle {
+        let row = self.row();
+        Rectangle::new(row.x, row.y, row.height, row.height)
+    }
+}
+
+fn text(text: &str) -> CString {
+    CString::new(text).unwrap()
+}
+
+/// draws a slider and returns its value only while it's being dragged,
+/// since raygui clamps the value to the range even when it's left alone,
+/// and the keys can set values outside it
+fn slider(
+    draw_handle: &mut RaylibDrawHandle,
+    bounds: Rectangle,
+    label: &str,
+    value_text: &str,
+    value: f32,
+    (min, max): (f32, f32),
+) -> Option<f32> {
+    let value = draw_handle.gui_slider(
+        bounds,
+        Some(&text(label)),
+        Some(&text(value_text)),
+        value,
+        min,
+        max,
+    );
+    let dragged = draw_handle.is_mouse_button_down(MouseButton::MOUSE_LEFT_BUTTON)
+        && bounds.check_collision_point_rec(draw_handle.get_mouse_position());
+    dragged.then_some(value)
+}
+
+/// draws the panel on the right of the window and changes the settings with its controls,
+/// where `seed_layouts` and `seed_colors` are the options for the combo boxes
+/// and `stats` are lines of text to show
+#[allow(clippy::too_many_lines)]
+pub fn draw(
+    draw_handle: &mut RaylibDrawHandle,
+    settings: &mut Settings,
+    seed_layouts: &[SeedLayout],
+    seed_colors: &[SeedColors],
+    stats: &[String],
+) -> Option<PanelAction> {
+    let x = draw_handle.get_screen_width() as f32 - PANEL_WIDTH;
+    draw_handle.gui_panel(Rectangle::new(
+        x,
+        0.0,
+        PANEL_WIDTH,
+        draw_handle.get_screen_height() as f32,
+    ));
+    let mut layout = Layout { x, y: MARGIN };
+
+    settings.paused =
+        draw_handle.gui_check_box(layout.checkbox(), Some(&text("paused")), settings.paused);
+    settings.highlight_border = draw_handle.gui_check_box(
+        layout.checkbox(),
+        Some(&text("highlight border")),
+        settings.highlight_border,
+    );
+    settings.show_hud = draw_handle.gui_check_box(
+        layout.checkbox(),
+        Some(&text("show hud")),
+        settings.show_hud,
+    );
+    settings.show_inspector = draw_handle.gui_check_box(
+        layout.checkbox(),
+        Some(&text("inspect cells")),
+        settings.show_inspector,
+    );
+    // speed on a log scale
+    if let Some(speed) = slider(
+        draw_handle,
+        layout.labeled_row(),
+        "speed",
+        &format!("{}", settings.radius_per_second),
+        settings.radius_per_second.log2(),
+        (-4.0, 12.0),
+    ) {
+        settings.radius_per_second = speed.round().exp2();
+    }
+    if let Some(seed_count) = slider(
+        draw_handle,
+        layout.labeled_row(),
+        "seeds",
+        &settings.seed_count.to_string(),
+        settings.seed_count as f32,
+        (0.0, 64.0),
+    ) {
+        settings.seed_count = seed_count.round() as usize;
+    }
+    if let Some(color_step) = slider(
+        draw_handle,
+        layout.labeled_row(),
+        "mutation",
+        &settings.color_step.to_string(),
+        settings.color_step as f32,
+        (0.0, 32.0),
+    ) {
+        settings.color_step = color_step.round() as i32;
+    }
+    if let Some(target_strength) = &mut settings.target_strength {
+        if let Some(value) = slider(
+            draw_handle,
+            layout.labeled_row(),
+            "target",
+            &format!("{target_strength:.2}"),
+            *target_strength,
+            (0.0, 1.0),
+        ) {
+            *target_strength = value;
+        }
+    }
+    if let Some(use_learned_mutation) = &mut settings.use_learned_mutation {
+        *use_learned_mutation = draw_handle.gui_check_box(
+            layout.checkbox(),
+            Some(&text("learned mutation")),
+            *use_learned_mutation,
+        );
+    }
+
+    // the options are found by name and only cloned when they're changed,
+    // since the seed colors can be a whole image
+    let names = |names: Vec<&str>| text(&names.join(";"));
+    let index = seed_layouts
+        .iter()
+        .position(|seed_layout| seed_layout.name() == settings.seed_layout.name())
+        .unwrap_or(0);
+    let row = layout.label_row(draw_handle, "seed layout");
+    let new_index = draw_handle.gui_combo_box(
+        row,
+        Some(&names(seed_layouts.iter().map(SeedLayout::name).collect())),
+        index as i32,
+    ) as usize;
+    if new_index != index {
+        settings.seed_layout = seed_layouts[new_index].clone();
+    }
+    let index = seed_colors
+        .iter()
+        .position(|seed_colors| seed_colors.name() == settings.seed_colors.name())
+        .unwrap_or(0);
+    let row = layout.label_row(draw_handle, "seed colors");
+    let new_index = draw_handle.gui_combo_box(
+        row,
+        Some(&names(seed_colors.iter().map(SeedColors::name).collect())),
+        index as i32,
+    ) as usize;
+    if new_index != index {
+        settings.seed_colors = seed_colors[new_index].clone();
+    }
+    draw_handle.gui_label(layout.row(), Some(&text("brush color")));
+    let row = layout.row();
+    let brush_index = match settings.brush_color {
+        BrushColor::Random => 0,
+        BrushColor::Fixed(..) => 1,
+        BrushColor::Sampled => 2,
+    };
+    let brush_index = draw_handle.gui_toggle_group(
+        Rectangle::new(row.x, row.y, row.width / 3.0 - 2.0, row.height),
+        Some(&text("random;picked;sampled")),
+        brush_index,
+    );
+    let (r, g, b) = settings.picked_color;
+    settings.brush_color = match brush_index {
+        0 => BrushColor::Random,
+        1 => BrushColor::Fixed(r, g, b),
+        _ => BrushColor::Sampled,
+    };
+
+    layout.y += MARGIN;
+    for line in stats {
+        draw_handle.gui_label(layout.row(), Some(&text(line)));
+    }
+
+    layout.y += MARGIN;
+    let mut action = None;
+    for pair in [
+        [
+            ("reset", PanelAction::Reset),
+            ("export image", PanelAction::ExportImage),
+        ],
+        [
+            ("save dish", PanelAction::SaveDish),
+            ("load dish", PanelAction::LoadDish),
+        ],
+    ] {
+        let row = layout.row();
+        for (i, (label, button_action)) in pair.into_iter().enumerate() {
+            let bounds = Rectangle::new(
+                (row.width / 2.0).mul_add(i as f32, row.x),
+                row.y,
+                row.width / 2.0 - 4.0,
+                row.height,
+            );
+            if draw_handle.gui_button(bounds, Some(&text(label))) {
+                action = Some(button_action);
+            }
+        }
+    }
+    action
+}
+
+/// draws a slider along the bottom of the window for scrubbing through the history,
+/// left of the panel when `panel_shown`, and returns the position while it's dragged
+pub fn draw_timeline(
+    draw_handle: &mut RaylibDrawHandle,
+    position: usize,
+    len: usize,
+    panel_shown: bool,
+) -> Option<usize> {
+    let width = draw_handle.get_screen_width() as f32 - if panel_shown { PANEL_WIDTH } else { 0.0 };
+    let y = draw_handle.get_screen_height() as f32 - TIMELINE_HEIGHT;
+    let rect = Rectangle::new(
+        MARGIN + LABEL_WIDTH,
+        y,
+        2.0f32.mul_add(-LABEL_WIDTH, 2.0f32.mul_add(-MARGIN, width)),
+        ROW_HEIGHT - 6.0,
+    );
+    // a long history can't be represented exactly by the slider, so it only seeks while it's dragged
+    slider(
+        draw_handle,
+        rect,
+        "timeline",
+        &format!("{position} / {len}"),
+        position as f32,
+        (0.0, len as f32),
+    )
+    .map(|position| position.round() as usize)
+}
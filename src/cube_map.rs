@@ -0,0 +1,163 @@
+use crate::dish::Dish;
+use crate::topology::Topology;
+
+/// the normal, the direction of increasing col, and the direction of increasing row of each face,
+/// in the order and orientation of opengl cube maps: +x, -x, +y, -y, +z, -z
+const FACES: [[[i32; 3]; 3]; 6] = [
+    [[1, 0, 0], [0, 0, -1], [0, -1, 0]],
+    [[-1, 0, 0], [0, 0, 1], [0, -1, 0]],
+    [[0, 1, 0], [1, 0, 0], [0, 0, 1]],
+    [[0, -1, 0], [1, 0, 0], [0, 0, -1]],
+    [[0, 0, 1], [1, 0, 0], [0, -1, 0]],
+    [[0, 0, -1], [-1, 0, 0], [0, -1, 0]],
+];
+
+const FACE_NAMES: [&str; 6] = ["px", "nx", "py", "ny", "pz", "nz"];
+
+fn dot(a: [i32; 3], b: [i32; 3]) -> i32 {
+    a.into_iter().zip(b).map(|(a_i, b_i)| a_i * b_i).sum()
+}
+
+fn dot_f32(a: [i32; 3], b: [f32; 3]) -> f32 {
+    a.into_iter()
+        .zip(b)
+        .map(|(a_i, b_i)| a_i as f32 * b_i)
+        .sum()
+}
+
+fn scale(a: [i32; 3], k: i32) -> [i32; 3] {
+    a.map(|a_i| a_i * k)
+}
+
+const fn add(a: [i32; 3], b: [i32; 3]) -> [i32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// the surface of a cube with `size` by `size` cells on each face,
+/// where cell `(face * size + row) * size + col` is at (row, col) on the face.
+/// cells on the edge of a face are adjacent to the cells across the edge on the next face,
+/// so there are no seams
+#[derive(Clone, Copy, Debug)]
+pub struct CubeMap {
+    size: usize,
+}
+
+impl CubeMap {
+    pub const fn new(size: usize) -> Self {
+        Self { size }
+    }
+
+    pub const fn cell(self, face: usize, row: usize, col: usize) -> usize {
+        (face * self.size + row) * self.size + col
+    }
+
+    pub const fn face_row_col(self, cell: usize) -> (usize, usize, usize) {
+        (
+            cell / (self.size * self.size),
+            cell / self.size % self.size,
+            cell % self.size,
+        )
+    }
+
+    /// the cell at the point on the surface of the cube with side length 2 * size centered at the origin,
+    /// where adjacent cell centers are 2 apart
+    fn cell_at_point(self, point: [i32; 3]) -> usize {
+        let face = FACES
+            .iter()
+            .position(|&[normal, _, _]| dot(point, normal) == self.size as i32)
+            .unwrap();
+        let [_, col_axis, row_axis] = FACES[face];
+        let col = (dot(point, col_axis) + self.size as i32 - 1) / 2;
+        let row = (dot(point, row_axis) + self.size as i32 - 1) / 2;
+        self.cell(face, row as usize, col as usize)
+    }
+
+    /// the cell that the direction from the center of the cube points at
+    pub fn cell_at_direction(self, direction: [f32; 3]) -> usize {
+        let [normal, col_axis, row_axis] = FACES
+            .into_iter()
+            .max_by(|[normal, _, _], [normal_1, _, _]| {
+                dot_f32(*normal, direction).total_cmp(&dot_f32(*normal_1, direction))
+            })
+            .unwrap();
+        let face = FACES.iter().position(|face| face[0] == normal).unwrap();
+        let depth = dot_f32(normal, direction);
+        let to_index = |axis: [i32; 3]| {
+            let coordinate = dot_f32(axis, direction) / depth;
+            ((coordinate.mul_add(0.5, 0.5) * self.size as f32) as usize).min(self.size - 1)
+        };
+        self.cell(face, to_index(row_axis), to_index(col_axis))
+    }
+}
+
+impl Topology for CubeMap {
+    fn cell_count(&self) -> usize {
+        6 * self.size * self.size
+    }
+
+    fn direction_count(&self) -> usize {
+        4
+    }
+
+    fn neighbor(&self, cell: usize, direction: usize) -> Option<usize> {
+        let (face, row, col) = self.face_row_col(cell);
+        let [normal, col_axis, row_axis] = FACES[face];
+        let size = self.size as i32;
+        let col = 2 * col as i32 + 1 - size;
+        let row = 2 * row as i32 + 1 - size;
+        let point = add(
+            scale(normal, size),
+            add(scale(col_axis, col), scale(row_axis, row)),
+        );
+        let (axis, sign) = match direction {
+            0 => (col_axis, 1),
+            1 => (row_axis, 1),
+            2 => (col_axis, -1),
+            3 => (row_axis, -1),
+            _ => unreachable!("direction out of range"),
+        };
+        let point_1 = add(point, scale(axis, 2 * sign));
+        if dot(point_1, axis).abs() < size {
+            return Some(self.cell_at_point(point_1));
+        }
+        // wrap around the edge onto the face whose normal is the direction we stepped in,
+        // into the cells next to the edge
+        let point_1 = add(add(point, scale(axis, sign)), scale(normal, -1));
+        Some(self.cell_at_point(point_1))
+    }
+}
+
+impl Dish<CubeMap> {
+    /// saves a png for each face, named like `px.png` for the +x face
+    pub fn save_faces(&self, dir: &std::path::Path) {
+        let cube_map = self.topology();
+        std::fs::create_dir_all(dir).unwrap();
+        for (face, name) in FACE_NAMES.iter().enumerate() {
+            let image =
+                image::ImageBuffer::from_fn(cube_map.size as u32, cube_map.size as u32, |x, y| {
+                    let cell = cube_map.cell(face, y as usize, x as usize);
+                    let color = self.states()[cell].to_color();
+                    image::Rgb([color.r, color.g, color.b])
+                });
+            image.save(dir.join(format!("{name}.png"))).unwrap();
+        }
+    }
+
+    /// saves the equirectangular projection, with +y up and the center of the image facing +z
+    pub fn save_equirectangular(&self, path: &std::path::Path, width: u32) {
+        let cube_map = self.topology();
+        let height = width / 2;
+        let image = image::ImageBuffer::from_fn(width, height, |x, y| {
+            let longitude = ((x as f32 + 0.5) / width as f32 - 0.5) * std::f32::consts::TAU;
+            let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * std::f32::consts::PI;
+            let cell = cube_map.cell_at_direction([
+                latitude.cos() * longitude.sin(),
+                latitude.sin(),
+                latitude.cos() * longitude.cos(),
+            ]);
+            let color = self.states()[cell].to_color();
+            image::Rgb([color.r, color.g, color.b])
+        });
+        image.save(path).unwrap();
+    }
+}
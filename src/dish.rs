@@ -1,38 +1,123 @@
 use raylib::prelude::*;
 
 use crate::bijective_finite_sequence::BijectiveFiniteSequence;
+use crate::grid::{Grid, Tiling};
+use crate::history::History;
+use crate::learned_mutation::LearnedMutation;
+use crate::lineage::Lineage;
 use crate::my_rng::Rng;
 use crate::state::State;
+use crate::topology::Topology;
+use crate::viewport::Viewport;
 
 // TODO: maybe if border stored empty pixels it can be faster?
 
+/// circumradius in pixels of the hexagons in saved images
+const HEX_IMAGE_RADIUS: f32 = 4.0;
+
 #[derive(Clone, Debug)]
-pub struct Dish {
-    states: Vec<Vec<State>>, // TODO: this should be a 2d vec, not a Vec<Vec<_>>
-    /// holds states that are empty and adjacent to states that are filled
-    border: BijectiveFiniteSequence<(usize, usize)>,
+pub struct Dish<T: Topology> {
+    topology: T,
+    states: Vec<State>,
+    /// holds states that are filled and adjacent to states that are empty
+    border: BijectiveFiniteSequence<usize>,
+    /// the color of each cell that new states are pulled toward
+    target: Option<Vec<(u8, u8, u8)>>,
+    /// how strongly new states are pulled toward the target, from 0 to 1
+    target_strength: f32,
+    /// used instead of `color_step` to mutate new states
+    learned_mutation: Option<LearnedMutation>,
+    /// how strongly new states mutate at each cell, from 0 to 1
+    mutation_strengths: Option<Vec<f32>>,
+    /// how likely growth is in each direction of each cell, at `cell * direction_count + direction`
+    direction_weights: Option<Vec<f32>>,
+    max_direction_weight: f32,
+    /// cells whose states changed since the last `take_changed`, if it's been called
+    changed: Option<Vec<usize>>,
+    /// where each cell was filled from, if it's being recorded
+    lineage: Option<Lineage>,
+    /// every change to the states, if it's being recorded
+    history: Option<History>,
+    /// how many cells are filled, kept up to date so it doesn't have to be counted every frame
+    filled_count: usize,
 }
 
-impl Dish {
-    pub fn new(size: usize) -> Self {
+impl<T: Topology> Dish<T> {
+    pub fn new(topology: T) -> Self {
         Self {
-            states: vec![vec![State::Empty; size]; size],
+            states: vec![State::Empty; topology.cell_count()],
+            topology,
             border: BijectiveFiniteSequence::new(),
+            target: None,
+            target_strength: 0.0,
+            learned_mutation: None,
+            mutation_strengths: None,
+            direction_weights: None,
+            max_direction_weight: 0.0,
+            changed: None,
+            lineage: None,
+            history: None,
+            filled_count: 0,
         }
     }
 
-    pub fn from_seed_count(rng: &mut Rng, size: usize, seed_count: usize) -> Self {
-        let mut slf = Self::new(size);
-        for _ in 0..seed_count {
-            slf.insert_seed(
-                rng.next_u32_n(size as u32) as usize,
-                rng.next_u32_n(size as u32) as usize,
-                State::random_filled(rng),
-            );
+    pub fn from_seed_count(rng: &mut Rng, topology: T, seed_count: usize) -> Self {
+        let mut slf = Self::new(topology);
+        slf.insert_random_seeds(rng, seed_count);
+        slf
+    }
+
+    /// starts with the given states, like obstacles and cells outside the domain
+    pub fn from_states(topology: T, states: Vec<State>) -> Self {
+        assert_eq!(states.len(), topology.cell_count());
+        let filled_count = states
+            .iter()
+            .filter(|state| matches!(state, State::Filled { .. }))
+            .count();
+        let mut slf = Self {
+            topology,
+            states,
+            border: BijectiveFiniteSequence::new(),
+            target: None,
+            target_strength: 0.0,
+            learned_mutation: None,
+            mutation_strengths: None,
+            direction_weights: None,
+            max_direction_weight: 0.0,
+            changed: None,
+            lineage: None,
+            history: None,
+            filled_count,
+        };
+        for cell in 0..slf.states.len() {
+            if slf.on_border(cell) {
+                slf.border.insert(cell);
+            }
         }
         slf
     }
 
+    /// inserts seeds at uniformly random cells that can be filled
+    pub fn insert_random_seeds(&mut self, rng: &mut Rng, seed_count: usize) {
+        let can_be_filled = |state: &State| matches!(state, State::Empty | State::Filled { .. });
+        if !self.states.iter().any(can_be_filled) {
+            return;
+        }
+        for _ in 0..seed_count {
+            let cell = loop {
+                let cell = rng.next_u32_n(self.states.len() as u32) as usize;
+                if can_be_filled(&self.states[cell]) {
+                    break cell;
+                }
+            };
+            self.insert_seed(cell, State::random_filled(rng));
+        }
+    }
+
+    pub const fn topology(&self) -> &T {
+        &self.topology
+    }
+
     pub fn is_done(&self) -> bool {
         self.border.is_empty()
     }
@@ -42,181 +127,455 @@ impl Dish {
         self.border.len() as f32
     }
 
-    // pub fn as_slice(&self) -> &[Vec<State>] {
-    //     &self.states
-    // }
-
-    pub fn insert_seed(&mut self, row: usize, col: usize, state: State) {
-        self.states[row][col] = state;
-
-        if self.on_border(row, col) {
-            self.border.insert((row, col));
-        }
-
-        for (row_1, col_1) in [
-            (row as i32, col as i32 + 1),
-            (row as i32 + 1, col as i32),
-            (row as i32, col as i32 - 1),
-            (row as i32 - 1, col as i32),
-        ] {
-            if (0..self.states.len() as i32).contains(&row_1)
-                && (0..self.states.len() as i32).contains(&col_1)
-            {
-                let row_1 = row_1 as usize;
-                let col_1 = col_1 as usize;
-                if self.on_border(row_1, col_1) {
-                    self.border.insert((row_1, col_1));
-                } else {
-                    self.border.remove(&(row_1, col_1));
-                }
+    pub const fn filled_count(&self) -> usize {
+        self.filled_count
+    }
+
+    pub fn states(&self) -> &[State] {
+        &self.states
+    }
+
+    pub fn state(&self, cell: usize) -> State {
+        self.states[cell]
+    }
+
+    /// whether the cell is filled and can grow into a neighbor
+    pub fn is_on_border(&self, cell: usize) -> bool {
+        self.border.contains(&cell)
+    }
+
+    /// starts recording the lineage of cells filled from now on
+    pub fn record_lineage(&mut self) {
+        self.lineage = Some(Lineage::new(self.states.len()));
+    }
+
+    pub const fn lineage(&self) -> Option<&Lineage> {
+        self.lineage.as_ref()
+    }
+
+    /// starts recording every change to the states from now on, so they can be replayed
+    pub fn record_history(&mut self) {
+        self.history = Some(History::default());
+    }
+
+    pub const fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
+    /// does nothing if the cell is an obstacle or outside the domain
+    pub fn insert_seed(&mut self, cell: usize, state: State) {
+        if !matches!(self.states[cell], State::Obstacle | State::Outside) {
+            self.set_state(cell, state);
+        }
+    }
+
+    /// does nothing if the cell is outside the domain
+    pub fn insert_obstacle(&mut self, cell: usize) {
+        if !matches!(self.states[cell], State::Outside) {
+            self.set_state(cell, State::Obstacle);
+        }
+    }
+
+    /// empties the cell if it's filled, so it can be grown into again
+    pub fn clear_cell(&mut self, cell: usize) {
+        if matches!(self.states[cell], State::Filled { .. }) {
+            self.set_state(cell, State::Empty);
+        }
+    }
+
+    /// empties the cells that are filled, like the cells in a rectangle, circle, or brush stroke
+    pub fn clear_region(&mut self, cells: impl IntoIterator<Item = usize>) {
+        for cell in cells {
+            self.clear_cell(cell);
+        }
+    }
+
+    /// sets the state whatever the cell was, like when replaying a history
+    pub fn set_state(&mut self, cell: usize, state: State) {
+        if let Some(history) = &mut self.history {
+            history.push(cell, self.states[cell], state);
+        }
+        let was_filled = matches!(self.states[cell], State::Filled { .. });
+        let is_filled = matches!(state, State::Filled { .. });
+        self.filled_count = self.filled_count + usize::from(is_filled) - usize::from(was_filled);
+        self.states[cell] = state;
+        if let Some(changed) = &mut self.changed {
+            changed.push(cell);
+        }
+        if let Some(lineage) = &mut self.lineage {
+            if matches!(state, State::Filled { .. }) {
+                lineage.record_seed(cell);
+            } else {
+                lineage.forget(cell);
+            }
+        }
+
+        if self.on_border(cell) {
+            self.border.insert(cell);
+        } else {
+            self.border.remove(&cell);
+        }
+
+        for cell_1 in self.topology.reverse_neighbors(cell) {
+            if self.on_border(cell_1) {
+                self.border.insert(cell_1);
+            } else {
+                self.border.remove(&cell_1);
             }
         }
     }
 
     /// returns whether the state is filled and has a neighbor that's empty
-    fn on_border(&self, row: usize, col: usize) -> bool {
-        let state = self.states[row][col];
+    fn on_border(&self, cell: usize) -> bool {
+        let state = self.states[cell];
         match state {
-            State::Empty => false,
-            State::Filled { .. } => [
-                (row as i32, col as i32 + 1),
-                (row as i32 + 1, col as i32),
-                (row as i32, col as i32 - 1),
-                (row as i32 - 1, col as i32),
-            ]
-            .into_iter()
-            .any(|(row_1, col_1)| {
-                usize::try_from(row_1).map_or(false, |row_1| {
-                    usize::try_from(col_1).map_or(false, |col_1| {
-                        self.states.get(row_1).map_or(false, |line_1| {
-                            line_1
-                                .get(col_1)
-                                .map_or(false, |state_1| matches!(state_1, State::Empty))
-                        })
-                    })
-                })
-                // if (0..self.states.len() as i32).contains(&row_1)
-                //     && (0..self.states.len() as i32).contains(&col_1)
-                // {
-                //     matches!(self.states[row_1 as usize][col_1 as usize], State::Empty)
-                // } else {
-                //     false
-                // }
-            }),
+            State::Empty | State::Obstacle | State::Outside => false,
+            State::Filled { .. } => self
+                .topology
+                .neighbors(cell)
+                .any(|cell_1| matches!(self.states[cell_1], State::Empty)),
         }
     }
 
+    /// pulls new states toward the color of the target at their cell
+    pub fn set_target(&mut self, target: Vec<(u8, u8, u8)>, strength: f32) {
+        assert_eq!(target.len(), self.states.len());
+        self.target = Some(target);
+        self.set_target_strength(strength);
+    }
+
+    /// the cells whose states changed since the last call, possibly repeated,
+    /// or none on the first call since changes weren't tracked until then
+    pub const fn take_changed(&mut self) -> Option<Vec<usize>> {
+        self.changed.replace(Vec::new())
+    }
+
+    pub fn set_learned_mutation(&mut self, learned_mutation: Option<LearnedMutation>) {
+        self.learned_mutation = learned_mutation;
+    }
+
+    pub fn set_mutation_strengths(&mut self, mutation_strengths: Vec<f32>) {
+        assert_eq!(mutation_strengths.len(), self.states.len());
+        self.mutation_strengths = Some(mutation_strengths);
+    }
+
+    pub fn set_direction_weights(&mut self, direction_weights: Vec<f32>) {
+        assert_eq!(
+            direction_weights.len(),
+            self.states.len() * self.topology.direction_count()
+        );
+        self.max_direction_weight = direction_weights.iter().copied().fold(0.0, f32::max);
+        assert!(
+            self.max_direction_weight > 0.0,
+            "some direction must have a positive weight"
+        );
+        self.direction_weights = Some(direction_weights);
+    }
+
+    pub fn set_target_strength(&mut self, strength: f32) {
+        assert!((0.0..=1.0).contains(&strength), "strength must be in 0..=1");
+        self.target_strength = strength;
+    }
+
     /// returns whether a step was taken (a step fails with probability > 0.5)
     pub fn maybe_step(&mut self, rng: &mut Rng, color_step: i32) -> bool {
         const DEBUG_PRINT: bool = false;
         // assert!(!self.is_done());
-        if let Some(&(row, col)) = self.border.get_random(rng) {
-            let state = self.states[row][col];
-            match state {
-                State::Empty => panic!("empty states should not be on the border"),
-                State::Filled { .. } => {
-                    let (row_1, col_1) = match rng.next(2) {
-                        0 => (row as i32, col as i32 + 1),
-                        1 => (row as i32 + 1, col as i32),
-                        2 => (row as i32, col as i32 - 1),
-                        3 => (row as i32 - 1, col as i32),
-                        _ => unreachable!("rng invariance violated"),
-                    };
-                    if let Ok(row_1) = usize::try_from(row_1) {
-                        if let Ok(col_1) = usize::try_from(col_1) {
-                            if let Some(line_1) = self.states.get(row_1) {
-                                if let Some(state_1) = line_1.get(col_1) {
-                                    if matches!(state_1, State::Empty) {
-                                        self.states[row_1][col_1] =
-                                            state.rand_step(rng, color_step);
-                                        let mut any_empty = false;
-                                        for (row_2, col_2) in [
-                                            (row_1 as i32, col_1 as i32 + 1),
-                                            (row_1 as i32 + 1, col_1 as i32),
-                                            (row_1 as i32, col_1 as i32 - 1),
-                                            (row_1 as i32 - 1, col_1 as i32),
-                                        ] {
-                                            if (0..self.states.len() as i32).contains(&row_2)
-                                                && (0..self.states.len() as i32).contains(&col_2)
-                                            {
-                                                any_empty |= matches!(
-                                                    self.states[row_2 as usize][col_2 as usize],
-                                                    State::Empty
-                                                );
-                                                if !self.on_border(row_2 as usize, col_2 as usize) {
-                                                    self.border
-                                                        .remove(&(row_2 as usize, col_2 as usize));
-                                                }
-                                            }
-                                        }
-                                        if any_empty {
-                                            self.border.insert((row_1, col_1));
-                                        }
-                                        if DEBUG_PRINT {
-                                            println!("updated");
-                                        }
-                                        return true;
-                                    }
-                                    if DEBUG_PRINT {
-                                        println!("not matches!(state_1, State::Empty)");
-                                    }
-                                } else if DEBUG_PRINT {
-                                    println!("not let Some(state_1) = line_1.get(col_1)");
-                                }
-                            } else if DEBUG_PRINT {
-                                println!("not let Some(line_1) = self.states.get(row_1)");
-                            }
-                        } else if DEBUG_PRINT {
-                            println!("not let Ok(col_1) = usize::try_from(col_1)");
-                        }
-                    } else if DEBUG_PRINT {
-                        println!("not let Ok(row_1) = usize::try_from(row_1)");
-                    }
+        let Some(&cell) = self.border.get_random(rng) else {
+            if DEBUG_PRINT {
+                println!("not let Some(&cell) = self.border.get_random(rng)");
+            }
+            return false;
+        };
+        let state = self.states[cell];
+        assert!(
+            matches!(state, State::Filled { .. }),
+            "only filled states should be on the border"
+        );
+        let direction = rng.next_u32_n(self.topology.direction_count() as u32) as usize;
+        if let Some(direction_weights) = &self.direction_weights {
+            // rejection sampling, so directions are taken in proportion to their weights
+            let weight = direction_weights[cell * self.topology.direction_count() + direction];
+            if rng.next_u32_n(1024) as f32 >= weight / self.max_direction_weight * 1024.0 {
+                if DEBUG_PRINT {
+                    println!("rejected direction");
                 }
+                return false;
+            }
+        }
+        let Some(cell_1) = self.topology.neighbor(cell, direction) else {
+            if DEBUG_PRINT {
+                println!("not let Some(cell_1) = self.topology.neighbor(cell, direction)");
+            }
+            return false;
+        };
+        if !matches!(self.states[cell_1], State::Empty) {
+            if DEBUG_PRINT {
+                println!("not matches!(state_1, State::Empty)");
+            }
+            return false;
+        }
+        let mutation_strength = self
+            .mutation_strengths
+            .as_ref()
+            .map_or(1.0, |mutation_strengths| mutation_strengths[cell_1]);
+        let mut state_1 = match &self.learned_mutation {
+            Some(learned_mutation) => {
+                let scale =
+                    |difference: i16| (difference as f32 * mutation_strength).round() as i16;
+                let (dr, dg, db) = learned_mutation.sample(rng);
+                state.shift((scale(dr), scale(dg), scale(db)))
             }
-        } else if DEBUG_PRINT {
-            println!("not let Some(&(row, col)) = self.border.get_random(rng)");
+            None if mutation_strength < 1.0 => {
+                // rounds randomly so weak mutation still happens sometimes
+                let color_step = (color_step as f32)
+                    .mul_add(mutation_strength, rng.next_u32_n(1024) as f32 / 1024.0)
+                    as i32;
+                state.rand_step(rng, color_step)
+            }
+            None => state.rand_step(rng, color_step),
+        };
+        if let Some(target) = &self.target {
+            state_1 = state_1.pull_toward(rng, target[cell_1], self.target_strength);
+        }
+        if let Some(history) = &mut self.history {
+            history.push(cell_1, State::Empty, state_1);
+        }
+        self.states[cell_1] = state_1;
+        self.filled_count += 1;
+        if let Some(changed) = &mut self.changed {
+            changed.push(cell_1);
+        }
+        if let Some(lineage) = &mut self.lineage {
+            lineage.record_growth(cell, cell_1);
+        }
+        for cell_2 in self.topology.reverse_neighbors(cell_1) {
+            if !self.on_border(cell_2) {
+                self.border.remove(&cell_2);
+            }
+        }
+        if self.on_border(cell_1) {
+            self.border.insert(cell_1);
         }
-        false
+        if DEBUG_PRINT {
+            println!("updated");
+        }
+        true
     }
 
-    pub fn save_to_image(&self, path: &std::path::Path, highlight_border: bool) {
-        let mut image = image::ImageBuffer::new(self.states.len() as u32, self.states.len() as u32);
+    pub fn validate(&self) {
+        for cell in 0..self.states.len() {
+            assert_eq!(self.border.contains(&cell), self.on_border(cell));
+            for cell_1 in self.topology.neighbors(cell) {
+                assert!(self
+                    .topology
+                    .reverse_neighbors(cell_1)
+                    .any(|cell_2| cell_2 == cell));
+            }
+        }
+        self.border.validate();
+        assert_eq!(
+            self.filled_count,
+            self.states
+                .iter()
+                .filter(|state| matches!(state, State::Filled { .. }))
+                .count()
+        );
+    }
+}
+
+impl Dish<Grid> {
+    /// saves the state of each cell as a pixel at its row and col, to be loaded with `load_states`
+    pub fn save_states(&self, path: &std::path::Path) {
+        let size = self.topology.size() as u32;
+        image::RgbaImage::from_fn(size, size, |x, y| {
+            let cell = self.topology.cell(y as usize, x as usize);
+            image::Rgba(self.states[cell].to_rgba())
+        })
+        .save(path)
+        .unwrap();
+    }
 
-        for (x, y, pixel) in image.enumerate_pixels_mut() {
-            let color = self.states[y as usize][x as usize].to_color();
-            *pixel = image::Rgb([color.r, color.g, color.b]);
+    /// the states saved with `save_states`, or why they couldn't be loaded
+    pub fn load_states(grid: &Grid, path: &std::path::Path) -> Result<Vec<State>, String> {
+        let image = image::open(path)
+            .map_err(|error| error.to_string())?
+            .into_rgba8();
+        if image.width() as usize != grid.size() || image.height() as usize != grid.size() {
+            return Err(format!(
+                "it's {}x{} but the dish is {}x{}",
+                image.width(),
+                image.height(),
+                grid.size(),
+                grid.size()
+            ));
         }
-        if highlight_border {
-            for (row, col) in self.border.as_slice().iter().copied() {
-                image.put_pixel(col as u32, row as u32, image::Rgb::from([255, 255, 255]));
+        Ok((0..grid.cell_count())
+            .map(|cell| {
+                let (row, col) = grid.row_col(cell);
+                State::from_rgba(image.get_pixel(col as u32, row as u32).0)
+            })
+            .collect())
+    }
+
+    /// cells outside the domain are transparent, and the image is cropped to the domain
+    pub fn save_to_image(&self, path: &std::path::Path, highlight_border: bool) {
+        let scale = match self.topology.tiling() {
+            Tiling::Square => 1.0,
+            Tiling::Hex => HEX_IMAGE_RADIUS,
+        };
+        let (width, height) = self.topology.extent();
+        let cell_at = |x: u32, y: u32| {
+            self.topology
+                .cell_at((x as f32 + 0.5) / scale, (y as f32 + 0.5) / scale)
+                .filter(|&cell| !matches!(self.states[cell], State::Outside))
+        };
+        let image = image::ImageBuffer::from_fn(
+            (scale * width).ceil() as u32,
+            (scale * height).ceil() as u32,
+            |x, y| {
+                cell_at(x, y).map_or(image::Rgba([0, 0, 0, 0]), |cell| {
+                    if highlight_border && self.border.contains(&cell) {
+                        image::Rgba([255, 255, 255, 255])
+                    } else {
+                        let color = self.states[cell].to_color();
+                        image::Rgba([color.r, color.g, color.b, color.a])
+                    }
+                })
+            },
+        );
+
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+        for (x, y, _) in image.enumerate_pixels() {
+            if cell_at(x, y).is_some() {
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
             }
         }
-        image.save(path).unwrap();
+        if min_x > max_x {
+            // nothing is in the domain
+            image.save(path).unwrap();
+            return;
+        }
+        image::imageops::crop_imm(&image, min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+            .to_image()
+            .save(path)
+            .unwrap();
     }
 
-    pub fn draw(&self, draw_handle: &mut RaylibDrawHandle, highlight_border: bool) {
-        // TODO: parallel write to texture?
-        for (row, line) in self.states.iter().enumerate() {
-            for (col, state) in line.iter().enumerate() {
-                if matches!(state, State::Filled { .. }) {
-                    draw_handle.draw_pixel(col as i32, row as i32, state.to_color());
-                }
+    pub fn draw(
+        &self,
+        draw_handle: &mut RaylibDrawHandle,
+        highlight_border: bool,
+        viewport: Viewport,
+    ) {
+        if viewport.scale() < self.topology.unit_scale() {
+            self.draw_averaged(draw_handle, highlight_border, viewport);
+            return;
+        }
+        // only the cells in the window
+        let (x_0, y_0) = viewport.to_grid(0.0, 0.0);
+        let (x_1, y_1) = viewport.to_grid(
+            draw_handle.get_screen_width() as f32,
+            draw_handle.get_screen_height() as f32,
+        );
+        for cell in self.topology.cells_in_rect(x_0, y_0, x_1, y_1) {
+            let state = self.states[cell];
+            if !matches!(state, State::Empty | State::Outside) {
+                self.draw_cell(draw_handle, viewport, cell, state.to_color());
             }
         }
         if highlight_border {
-            for (row, col) in self.border.as_slice().iter().copied() {
-                draw_handle.draw_pixel(col as i32, row as i32, raylib::color::Color::WHITE);
+            self.draw_border(draw_handle, viewport);
+        }
+    }
+
+    /// draws the cells on the border in white
+    pub fn draw_border(&self, draw_handle: &mut RaylibDrawHandle, viewport: Viewport) {
+        for cell in self.border.as_slice().iter().copied() {
+            self.draw_cell(draw_handle, viewport, cell, Color::WHITE);
+        }
+    }
+
+    fn draw_cell(
+        &self,
+        draw_handle: &mut RaylibDrawHandle,
+        viewport: Viewport,
+        cell: usize,
+        color: Color,
+    ) {
+        let scale = viewport.scale();
+        match self.topology.tiling() {
+            Tiling::Square => {
+                let (row, col) = self.topology.row_col(cell);
+                let (x, y) = viewport.to_screen(col as f32, row as f32);
+                draw_handle.draw_rectangle_v(
+                    Vector2::new(x, y),
+                    Vector2::new(scale.max(1.0), scale.max(1.0)),
+                    color,
+                );
+            }
+            Tiling::Hex => {
+                let (x, y) = self.topology.center(cell);
+                let (x, y) = viewport.to_screen(x, y);
+                draw_handle.draw_poly(Vector2::new(x, y), 6, scale, 0.0, color);
             }
         }
     }
 
-    pub fn validate(&self) {
-        for row in 0..self.states.len() {
-            for col in 0..self.states.len() {
-                assert_eq!(self.border.contains(&(row, col)), self.on_border(row, col));
+    /// draws each pixel as the average of the cells whose centers are in it,
+    /// for when there's more than one cell per pixel
+    fn draw_averaged(
+        &self,
+        draw_handle: &mut RaylibDrawHandle,
+        highlight_border: bool,
+        viewport: Viewport,
+    ) {
+        let width = draw_handle.get_screen_width().max(0) as usize;
+        let height = draw_handle.get_screen_height().max(0) as usize;
+        let pixel = |cell| {
+            let (x, y) = self.topology.center(cell);
+            let (x, y) = viewport.to_screen(x, y);
+            ((0.0..width as f32).contains(&x) && (0.0..height as f32).contains(&y))
+                .then(|| y as usize * width + x as usize)
+        };
+        // the sum of the red, green, and blue, and the number of cells in each pixel
+        let mut sums = vec![[0u32; 4]; width * height];
+        let (x_0, y_0) = viewport.to_grid(0.0, 0.0);
+        let (x_1, y_1) = viewport.to_grid(width as f32, height as f32);
+        for cell in self.topology.cells_in_rect(x_0, y_0, x_1, y_1) {
+            // empty cells are the black background
+            if matches!(self.states[cell], State::Outside) {
+                continue;
+            }
+            if let Some(pixel) = pixel(cell) {
+                let color = self.states[cell].to_color();
+                let sum = &mut sums[pixel];
+                sum[0] += color.r as u32;
+                sum[1] += color.g as u32;
+                sum[2] += color.b as u32;
+                sum[3] += 1;
+            }
+        }
+        for (pixel, [r, g, b, count]) in sums.into_iter().enumerate() {
+            if count > 0 && r + g + b > 0 {
+                draw_handle.draw_pixel(
+                    (pixel % width) as i32,
+                    (pixel / width) as i32,
+                    Color::new((r / count) as u8, (g / count) as u8, (b / count) as u8, 255),
+                );
+            }
+        }
+        if highlight_border {
+            for pixel in self.border.as_slice().iter().copied().filter_map(pixel) {
+                draw_handle.draw_pixel(
+                    (pixel % width) as i32,
+                    (pixel / width) as i32,
+                    Color::WHITE,
+                );
             }
         }
-        self.border.validate();
     }
 }
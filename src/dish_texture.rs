@@ -0,0 +1,121 @@
+use raylib::prelude::*;
+
+use crate::dish::Dish;
+use crate::grid::{Grid, Tiling};
+use crate::topology::Topology;
+use crate::viewport::Viewport;
+
+/// how many frames the mipmaps can be out of date for while zoomed out,
+/// since generating them costs about as much as uploading the whole texture
+const MIPMAP_FRAMES: u32 = 15;
+
+/// a square dish drawn as a texture with a texel for each cell,
+/// where only the cells that changed are redrawn, and only their rows are uploaded
+pub struct DishTexture {
+    /// rgba
+    pixels: Vec<u8>,
+    texture: Texture2D,
+    /// whether the texture has mipmaps for the pixels that changed
+    mipmaps: bool,
+    /// so they're generated at most every `MIPMAP_FRAMES` frames while the dish changes
+    frames_since_mipmaps: u32,
+}
+
+impl DishTexture {
+    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread, grid: &Grid) -> Self {
+        assert_eq!(
+            grid.tiling(),
+            Tiling::Square,
+            "only square dishes are textures"
+        );
+        let size = grid.size() as i32;
+        let texture = rl
+            .load_texture_from_image(thread, &Image::gen_image_color(size, size, Color::BLANK))
+            .unwrap();
+        Self {
+            pixels: vec![0; 4 * grid.cell_count()],
+            texture,
+            mipmaps: false,
+            frames_since_mipmaps: MIPMAP_FRAMES,
+        }
+    }
+
+    /// redraws the cells that changed, or every cell if the dish is new
+    pub fn update(&mut self, dish: &mut Dish<Grid>) {
+        let cells = dish
+            .take_changed()
+            .unwrap_or_else(|| (0..dish.states().len()).collect());
+        if !cells.is_empty() {
+            self.draw_cells(dish, cells);
+        }
+    }
+
+    /// redraws every cell, like when it starts drawing a different dish
+    pub fn redraw(&mut self, dish: &mut Dish<Grid>) {
+        dish.take_changed();
+        self.draw_cells(dish, 0..dish.states().len());
+    }
+
+    fn draw_cells(&mut self, dish: &Dish<Grid>, cells: impl IntoIterator<Item = usize>) {
+        let (mut first, mut last) = (usize::MAX, 0);
+        for cell in cells {
+            let color = dish.states()[cell].to_color();
+            self.pixels[4 * cell..4 * cell + 4]
+                .copy_from_slice(&[color.r, color.g, color.b, color.a]);
+            first = first.min(cell);
+            last = last.max(cell);
+        }
+        if first > last {
+            return;
+        }
+        // only the rows from the first cell that changed to the last
+        let grid = dish.topology();
+        let size = grid.size();
+        let ((first_row, _), (last_row, _)) = (grid.row_col(first), grid.row_col(last));
+        let rows = &self.pixels[4 * size * first_row..4 * size * (last_row + 1)];
+        // SAFETY: the rectangle is inside the texture, and `rows` has an rgba pixel for each texel in it
+        unsafe {
+            raylib::ffi::UpdateTextureRec(
+                *self.texture,
+                raylib::ffi::Rectangle {
+                    x: 0.0,
+                    y: first_row as f32,
+                    width: size as f32,
+                    height: (last_row + 1 - first_row) as f32,
+                },
+                rows.as_ptr().cast(),
+            );
+        }
+        self.mipmaps = false;
+    }
+
+    /// draws the dish in one call, with each pixel the average of its cells when zoomed out
+    pub fn draw(
+        &mut self,
+        draw_handle: &mut RaylibDrawHandle,
+        thread: &RaylibThread,
+        viewport: Viewport,
+    ) {
+        if viewport.scale() < 1.0 {
+            if !self.mipmaps && self.frames_since_mipmaps >= MIPMAP_FRAMES {
+                self.texture.gen_texture_mipmaps();
+                self.mipmaps = true;
+                self.frames_since_mipmaps = 0;
+            }
+            self.texture
+                .set_texture_filter(thread, TextureFilter::TEXTURE_FILTER_TRILINEAR);
+        } else {
+            self.texture
+                .set_texture_filter(thread, TextureFilter::TEXTURE_FILTER_POINT);
+        }
+        self.frames_since_mipmaps = self.frames_since_mipmaps.saturating_add(1);
+        let (x, y) = viewport.to_screen(0.0, 0.0);
+        draw_handle.draw_texture_ex(
+            &self.texture,
+            Vector2::new(x, y),
+            0.0,
+            viewport.scale(),
+            Color::WHITE,
+        );
+    }
+}
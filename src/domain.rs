@@ -0,0 +1,44 @@
+use crate::grid::Grid;
+use crate::topology::Topology;
+
+/// the region of a grid where cells exist, cells outside it are never filled or adjacent to anything
+#[derive(Clone, Debug)]
+pub enum Domain {
+    Full,
+    /// the largest centered circle
+    Circle,
+    /// vertices of a polygon, the inside is decided by the even-odd rule
+    Polygon(Vec<(f32, f32)>),
+    /// the opaque part of an image stretched over the grid
+    Image(image::RgbaImage),
+}
+
+impl Domain {
+    /// whether the center of each cell is in the domain
+    pub fn mask(&self, grid: &Grid) -> Vec<bool> {
+        (0..grid.cell_count())
+            .map(|cell| self.contains(grid, cell))
+            .collect()
+    }
+
+    fn contains(&self, grid: &Grid, cell: usize) -> bool {
+        let (x, y) = grid.unit_center(cell);
+        match self {
+            Self::Full => true,
+            Self::Circle => (x - 0.5).hypot(y - 0.5) <= 0.5,
+            Self::Polygon(vertices) => {
+                let mut inside = false;
+                for (&(x_0, y_0), &(x_1, y_1)) in
+                    vertices.iter().zip(vertices.iter().cycle().skip(1))
+                {
+                    // whether the ray going right from the point crosses the edge
+                    if (y_0 > y) != (y_1 > y) && x < (x_1 - x_0) * (y - y_0) / (y_1 - y_0) + x_0 {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+            Self::Image(image) => grid.sample_image_at(image, cell).0[3] >= 128,
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::neighborhood::{neighbor, Neighborhood};
+use crate::topology::Topology;
+
+/// how the cells of a grid are laid out when drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tiling {
+    /// each cell has 4 adjacent cells
+    Square,
+    /// pointy-top hexagons in "odd-r" offset coordinates, so odd rows are shifted right by half a cell.
+    /// each cell has 6 adjacent cells, which makes growth much more isotropic than on the square grid
+    Hex,
+}
+
+impl Tiling {
+    /// the neighborhood of adjacent cells
+    pub fn default_neighborhood(self) -> Neighborhood {
+        match self {
+            Self::Square => Neighborhood::von_neumann(1),
+            Self::Hex => Neighborhood::hex(),
+        }
+    }
+}
+
+/// a square of `size` by `size` cells, where cell `row * size + col` is at (row, col)
+#[derive(Clone, Debug)]
+pub struct Grid {
+    size: usize,
+    tiling: Tiling,
+    neighborhood: Neighborhood,
+    direction_count: usize,
+}
+
+impl Grid {
+    pub fn new(size: usize, tiling: Tiling, neighborhood: Neighborhood) -> Self {
+        let direction_count = neighborhood
+            .offsets(0)
+            .len()
+            .max(neighborhood.offsets(1).len());
+        Self {
+            size,
+            tiling,
+            neighborhood,
+            direction_count,
+        }
+    }
+
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    pub const fn tiling(&self) -> Tiling {
+        self.tiling
+    }
+
+    pub const fn cell(&self, row: usize, col: usize) -> usize {
+        row * self.size + col
+    }
+
+    pub const fn row_col(&self, cell: usize) -> (usize, usize) {
+        (cell / self.size, cell % self.size)
+    }
+
+    /// width and height of the drawn grid,
+    /// in units of the side length of square cells or the circumradius of hex cells
+    pub fn extent(&self) -> (f32, f32) {
+        let size = self.size as f32;
+        match self.tiling {
+            Tiling::Square => (size, size),
+            Tiling::Hex => (3f32.sqrt() * (size + 0.5), 1.5f32.mul_add(size - 1.0, 2.0)),
+        }
+    }
+
+    /// center of the drawn cell in 0..1 across the width and height of the grid,
+    /// which is how points are given for domains, seed layouts, mutation fields and wind
+    pub fn unit_center(&self, cell: usize) -> (f32, f32) {
+        let (width, height) = self.extent();
+        let (x, y) = self.center(cell);
+        (x / width, y / height)
+    }
+
+    /// the cell at a point in 0..1 across the width and height of the grid, like `unit_center`
+    pub fn unit_cell_at(&self, x: f32, y: f32) -> Option<usize> {
+        let (width, height) = self.extent();
+        self.cell_at(x * width, y * height)
+    }
+
+    /// center of the drawn cell, in the units of `extent`
+    pub fn center(&self, cell: usize) -> (f32, f32) {
+        let (row, col) = self.row_col(cell);
+        match self.tiling {
+            Tiling::Square => (col as f32 + 0.5, row as f32 + 0.5),
+            Tiling::Hex => (
+                3f32.sqrt() * (0.5f32.mul_add((row & 1) as f32, col as f32) + 0.5),
+                1.5f32.mul_add(row as f32, 1.0),
+            ),
+        }
+    }
+
+    /// the vector from the center of the cell to the center of its neighbor in the direction,
+    /// even if the neighbor is off the grid
+    pub fn direction_vector(&self, cell: usize, direction: usize) -> Option<(f32, f32)> {
+        let (row, _) = self.row_col(cell);
+        let &(row_offset, col_offset) = self.neighborhood.offsets(row).get(direction)?;
+        Some(match self.tiling {
+            Tiling::Square => (col_offset as f32, row_offset as f32),
+            Tiling::Hex => {
+                // odd rows are shifted right by half a cell
+                let shift = ((row as i32 + row_offset) & 1) - (row & 1) as i32;
+                (
+                    3f32.sqrt() * 0.5f32.mul_add(shift as f32, col_offset as f32),
+                    1.5 * row_offset as f32,
+                )
+            }
+        })
+    }
+
+    /// the cell drawn at the point, in the units of `extent`
+    pub fn cell_at(&self, x: f32, y: f32) -> Option<usize> {
+        let (row, col) = match self.tiling {
+            Tiling::Square => (y.floor() as i32, x.floor() as i32),
+            Tiling::Hex => hex_at(x - 3f32.sqrt() / 2.0, y - 1.0),
+        };
+        ((0..self.size as i32).contains(&row) && (0..self.size as i32).contains(&col))
+            .then(|| self.cell(row as usize, col as usize))
+    }
+
+    /// the cells whose centers are within the radius of the point, in the units of `extent`
+    pub fn cells_within(&self, x: f32, y: f32, radius: f32) -> Vec<usize> {
+        self.cells_in_rect(x - radius, y - radius, x + radius, y + radius)
+            .filter(|&cell| {
+                let (x_1, y_1) = self.center(cell);
+                (x_1 - x).hypot(y_1 - y) <= radius
+            })
+            .collect()
+    }
+
+    /// the cells whose centers are in the rectangle, in the units of `extent`
+    pub fn cells_inside_rect(&self, x_0: f32, y_0: f32, x_1: f32, y_1: f32) -> Vec<usize> {
+        self.cells_in_rect(x_0, y_0, x_1, y_1)
+            .filter(|&cell| {
+                let (x, y) = self.center(cell);
+                (x_0..=x_1).contains(&x) && (y_0..=y_1).contains(&y)
+            })
+            .collect()
+    }
+
+    /// the cells that might be drawn in the rectangle, in the units of `extent`
+    pub fn cells_in_rect(
+        &self,
+        x_0: f32,
+        y_0: f32,
+        x_1: f32,
+        y_1: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let (row_spacing, col_spacing) = match self.tiling {
+            Tiling::Square => (1.0, 1.0),
+            Tiling::Hex => (1.5, 3f32.sqrt()),
+        };
+        let rows = (y_0 / row_spacing - 1.0).max(0.0) as usize
+            ..(y_1 / row_spacing + 1.0).clamp(0.0, self.size as f32) as usize;
+        let cols = (x_0 / col_spacing - 1.0).max(0.0) as usize
+            ..(x_1 / col_spacing + 1.0).clamp(0.0, self.size as f32) as usize;
+        rows.flat_map(move |row| cols.clone().map(move |col| self.cell(row, col)))
+    }
+
+    /// how much to scale `extent` by to fit in a window of the given size
+    pub fn scale_to_fit(&self, width: f32, height: f32) -> f32 {
+        let (extent_width, extent_height) = self.extent();
+        (width / extent_width).min(height / extent_height)
+    }
+
+    /// how much to scale `extent` by so there's about one cell per pixel
+    pub fn unit_scale(&self) -> f32 {
+        let (extent_width, _) = self.extent();
+        self.size as f32 / extent_width
+    }
+
+    /// the pixel of the image at the center of the cell, with the image stretched over the grid
+    pub fn sample_image_at(&self, image: &image::RgbaImage, cell: usize) -> image::Rgba<u8> {
+        let (x, y) = self.unit_center(cell);
+        *image.get_pixel(
+            ((x * image.width() as f32) as u32).min(image.width() - 1),
+            ((y * image.height() as f32) as u32).min(image.height() - 1),
+        )
+    }
+
+    /// the pixel of the image at the center of each cell, with the image stretched over the grid
+    pub fn sample_image(&self, image: &image::RgbaImage) -> Vec<image::Rgba<u8>> {
+        (0..self.cell_count())
+            .map(|cell| self.sample_image_at(image, cell))
+            .collect()
+    }
+}
+
+impl Topology for Grid {
+    fn cell_count(&self) -> usize {
+        self.size * self.size
+    }
+
+    fn direction_count(&self) -> usize {
+        self.direction_count
+    }
+
+    fn neighbor(&self, cell: usize, direction: usize) -> Option<usize> {
+        let (row, col) = self.row_col(cell);
+        let &offset = self.neighborhood.offsets(row).get(direction)?;
+        neighbor(self.size, row, col, offset).map(|(row_1, col_1)| self.cell(row_1, col_1))
+    }
+
+    fn neighbors(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        let (row, col) = self.row_col(cell);
+        self.neighborhood
+            .neighbors(self.size, row, col)
+            .map(|(row_1, col_1)| self.cell(row_1, col_1))
+    }
+
+    fn reverse_neighbors(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        let (row, col) = self.row_col(cell);
+        self.neighborhood
+            .reverse_neighbors(self.size, row, col)
+            .map(|(row_1, col_1)| self.cell(row_1, col_1))
+    }
+}
+
+/// the hex cell containing the point, with circumradius 1 and the center of (0, 0) at (0, 0)
+fn hex_at(x: f32, y: f32) -> (i32, i32) {
+    // convert to cube coordinates and round to the nearest cube
+    let cube_q = (3f32.sqrt() / 3.0).mul_add(x, -y / 3.0);
+    let cube_r = 2.0 / 3.0 * y;
+    let cube_s = -cube_q - cube_r;
+    let (mut q_1, mut r_1, s_1) = (cube_q.round(), cube_r.round(), cube_s.round());
+    let q_diff = (q_1 - cube_q).abs();
+    let r_diff = (r_1 - cube_r).abs();
+    let s_diff = (s_1 - cube_s).abs();
+    if q_diff > r_diff && q_diff > s_diff {
+        q_1 = -r_1 - s_1;
+    } else if r_diff > s_diff {
+        r_1 = -q_1 - s_1;
+    }
+    let row = r_1 as i32;
+    let col = q_1 as i32 + (row - (row & 1)) / 2;
+    (row, col)
+}
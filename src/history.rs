@@ -0,0 +1,97 @@
+use crate::dish::Dish;
+use crate::grid::Grid;
+use crate::state::State;
+
+/// a cell's state changing, with what it was so it can be undone
+#[derive(Clone, Copy, Debug)]
+struct Change {
+    cell: u32,
+    from: State,
+    to: State,
+}
+
+/// every change to a dish's states since it started recording, in order
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    changes: Vec<Change>,
+}
+
+impl History {
+    pub fn push(&mut self, cell: usize, from: State, to: State) {
+        self.changes.push(Change {
+            cell: cell as u32,
+            from,
+            to,
+        });
+    }
+
+    pub const fn len(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+/// a copy of a dish that replays its history, so it can be shown as it was after any number of changes
+/// without re-simulating, where seeking costs as much as the changes in between
+pub struct Timeline {
+    dish: Dish<Grid>,
+    /// how many changes of the history have been applied
+    position: usize,
+    /// how long the history was when it last caught up
+    end: usize,
+}
+
+impl Timeline {
+    /// starts at the end of the history, the same as the dish
+    pub fn new(dish: &Dish<Grid>) -> Self {
+        let end = dish.history().map_or(0, History::len);
+        Self {
+            dish: Dish::from_states(dish.topology().clone(), dish.states().to_vec()),
+            position: end,
+            end,
+        }
+    }
+
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    pub const fn dish(&self) -> &Dish<Grid> {
+        &self.dish
+    }
+
+    pub const fn dish_mut(&mut self) -> &mut Dish<Grid> {
+        &mut self.dish
+    }
+
+    /// moves to after the first `position` changes, clamped to the history
+    pub fn seek(&mut self, history: &History, position: usize) {
+        let position = position.min(history.len());
+        if position > self.position {
+            for change in &history.changes[self.position..position] {
+                self.dish.set_state(change.cell as usize, change.to);
+            }
+        } else {
+            for change in history.changes[position..self.position].iter().rev() {
+                self.dish.set_state(change.cell as usize, change.from);
+            }
+        }
+        self.position = position;
+    }
+
+    /// follows the changes to the dish while it's at the end,
+    /// returns false if the dish changed while it was replaying so it's out of date
+    pub fn catch_up(&mut self, history: &History) -> bool {
+        if self.position == self.end {
+            self.seek(history, history.len());
+            self.end = history.len();
+            true
+        } else {
+            self.end == history.len()
+        }
+    }
+
+    /// moves by a number of changes, backwards if it's negative
+    pub fn seek_by(&mut self, history: &History, changes: isize) {
+        self.seek(history, self.position.saturating_add_signed(changes));
+    }
+}
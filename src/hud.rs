@@ -0,0 +1,108 @@
+use raylib::prelude::*;
+
+const FONT_SIZE: i32 = 20;
+const HELP_FONT_SIZE: i32 = 16;
+/// pixels between lines
+const LINE_SPACING: i32 = 2;
+const MARGIN: i32 = 10;
+/// how many seconds of steps the rates are averaged over
+const RATE_PERIOD: f32 = 0.5;
+
+/// measures how many steps the dish actually takes, and how many calls to `Dish::maybe_step` fail
+#[derive(Clone, Debug, Default)]
+pub struct StepRate {
+    attempts: usize,
+    steps: usize,
+    elapsed: f32,
+    steps_per_second: f32,
+    failure_ratio: f32,
+}
+
+impl StepRate {
+    /// records the result of a call to `Dish::maybe_step`
+    pub const fn record(&mut self, taken: bool) {
+        self.attempts += 1;
+        self.steps += taken as usize;
+    }
+
+    /// updates the rates once every `RATE_PERIOD` seconds
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed += dt;
+        if self.elapsed >= RATE_PERIOD {
+            self.steps_per_second = self.steps as f32 / self.elapsed;
+            self.failure_ratio = if self.attempts == 0 {
+                0.0
+            } else {
+                (self.attempts - self.steps) as f32 / self.attempts as f32
+            };
+            self.attempts = 0;
+            self.steps = 0;
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub const fn steps_per_second(&self) -> f32 {
+        self.steps_per_second
+    }
+
+    /// the fraction of calls to `Dish::maybe_step` that didn't take a step
+    pub const fn failure_ratio(&self) -> f32 {
+        self.failure_ratio
+    }
+}
+
+/// draws the lines of text in the top left of the window over a dark background
+pub fn draw(draw_handle: &mut RaylibDrawHandle, lines: &[String]) {
+    draw_box(draw_handle, (0, 0), lines, FONT_SIZE);
+}
+
+/// draws the lines of text next to the point, kept inside the window
+pub fn draw_at(draw_handle: &mut RaylibDrawHandle, (x, y): (i32, i32), lines: &[String]) {
+    let (width, height) = box_size(lines, FONT_SIZE);
+    let x = (x + MARGIN)
+        .min(draw_handle.get_screen_width() - width)
+        .max(0);
+    let y = (y + MARGIN)
+        .min(draw_handle.get_screen_height() - height)
+        .max(0);
+    draw_box(draw_handle, (x, y), lines, FONT_SIZE);
+}
+
+/// draws the lines of text in the center of the window, smaller so the whole keymap fits
+pub fn draw_help(draw_handle: &mut RaylibDrawHandle, lines: &[String]) {
+    let (width, height) = box_size(lines, HELP_FONT_SIZE);
+    let x = (draw_handle.get_screen_width() - width) / 2;
+    let y = (draw_handle.get_screen_height() - height) / 2;
+    draw_box(draw_handle, (x.max(0), y.max(0)), lines, HELP_FONT_SIZE);
+}
+
+fn box_size(lines: &[String], font_size: i32) -> (i32, i32) {
+    let width = lines
+        .iter()
+        .map(|line| measure_text(line, font_size))
+        .max()
+        .unwrap_or(0);
+    (
+        width + 2 * MARGIN,
+        lines.len() as i32 * (font_size + LINE_SPACING) + 2 * MARGIN,
+    )
+}
+
+fn draw_box(
+    draw_handle: &mut RaylibDrawHandle,
+    (x, y): (i32, i32),
+    lines: &[String],
+    font_size: i32,
+) {
+    let (width, height) = box_size(lines, font_size);
+    draw_handle.draw_rectangle(x, y, width, height, Color::BLACK.fade(0.6));
+    for (i, line) in lines.iter().enumerate() {
+        draw_handle.draw_text(
+            line,
+            x + MARGIN,
+            y + MARGIN + i as i32 * (font_size + LINE_SPACING),
+            font_size,
+            Color::WHITE,
+        );
+    }
+}
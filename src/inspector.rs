@@ -0,0 +1,67 @@
+use crate::dish::Dish;
+use crate::grid::Grid;
+use crate::lineage::Lineage;
+use crate::state::State;
+
+/// what's known about the cell, for showing next to the mouse,
+/// with the lineage separate since the dish shown might be a copy that doesn't record it
+pub fn lines(dish: &Dish<Grid>, lineage: Option<&Lineage>, cell: usize) -> Vec<String> {
+    let (row, col) = dish.topology().row_col(cell);
+    let mut lines = vec![format!("row {row}, col {col}")];
+    match dish.state(cell) {
+        State::Empty => lines.push("empty".to_string()),
+        State::Obstacle => lines.push("obstacle".to_string()),
+        State::Outside => lines.push("outside".to_string()),
+        State::Filled { r, g, b } => {
+            let (hue, saturation, value) = rgb_to_hsv(r, g, b);
+            lines.push(format!("rgb {r} {g} {b}, #{r:02x}{g:02x}{b:02x}"));
+            lines.push(format!(
+                "hsv {hue:.0} {:.0}% {:.0}%",
+                100.0 * saturation,
+                100.0 * value
+            ));
+            if dish.is_on_border(cell) {
+                lines.push("on the border".to_string());
+            }
+            // only for filled cells, since while replaying the lineage is from later on
+            if let Some(lineage) = lineage {
+                lines.extend(lineage_lines(dish, lineage, cell));
+            }
+        }
+    }
+    lines
+}
+
+fn lineage_lines(dish: &Dish<Grid>, lineage: &Lineage, cell: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(fill_time) = lineage.fill_time(cell) {
+        lines.push(format!("filled {fill_time} cells in"));
+    }
+    if let Some(seed) = lineage.seed(cell) {
+        let (row, col) = dish.topology().row_col(seed);
+        lines.push(format!("seed at row {row}, col {col}"));
+    }
+    if let Some(parent) = lineage.parent(cell) {
+        let (row, col) = dish.topology().row_col(parent);
+        lines.push(format!("grew from row {row}, col {col}"));
+    }
+    lines
+}
+
+/// hue in degrees, saturation and value in 0..1
+fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let chroma = (max - r.min(g).min(b)) as f32;
+    let (r_f, g_f, b_f) = (r as f32, g as f32, b as f32);
+    let hue = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g_f - b_f) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b_f - r_f) / chroma + 2.0)
+    } else {
+        60.0 * ((r_f - g_f) / chroma + 4.0)
+    };
+    let saturation = if max == 0 { 0.0 } else { chroma / max as f32 };
+    (hue, saturation, max as f32 / 255.0)
+}
@@ -0,0 +1,437 @@
+use std::fmt::Write;
+
+use raylib::consts::KeyboardKey;
+use raylib::RaylibHandle;
+
+/// what a key can do in the window
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Reset,
+    Pause,
+    Slower,
+    Faster,
+    StepBackwards,
+    StepForwards,
+    SecondBackwards,
+    SecondForwards,
+    PlayForwards,
+    PlayBackwards,
+    FewerSeeds,
+    MoreSeeds,
+    NextSeedLayout,
+    NextSeedColors,
+    LessMutation,
+    MoreMutation,
+    WeakerTarget,
+    StrongerTarget,
+    ToggleLearnedMutation,
+    NextBrushColor,
+    Erase,
+    PaintObstacles,
+    Fit,
+    OneToOne,
+    SaveImage,
+    HighlightBorder,
+    Validate,
+    TogglePanel,
+    ToggleHud,
+    ToggleInspector,
+    Record,
+    Help,
+}
+
+impl Action {
+    /// in the order they're listed in the help and the config file
+    pub const ALL: [Self; 32] = [
+        Self::Reset,
+        Self::Pause,
+        Self::Slower,
+        Self::Faster,
+        Self::StepBackwards,
+        Self::StepForwards,
+        Self::SecondBackwards,
+        Self::SecondForwards,
+        Self::PlayForwards,
+        Self::PlayBackwards,
+        Self::FewerSeeds,
+        Self::MoreSeeds,
+        Self::NextSeedLayout,
+        Self::NextSeedColors,
+        Self::LessMutation,
+        Self::MoreMutation,
+        Self::WeakerTarget,
+        Self::StrongerTarget,
+        Self::ToggleLearnedMutation,
+        Self::NextBrushColor,
+        Self::Erase,
+        Self::PaintObstacles,
+        Self::Fit,
+        Self::OneToOne,
+        Self::SaveImage,
+        Self::HighlightBorder,
+        Self::Validate,
+        Self::TogglePanel,
+        Self::ToggleHud,
+        Self::ToggleInspector,
+        Self::Record,
+        Self::Help,
+    ];
+
+    /// the name in the config file
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Reset => "reset",
+            Self::Pause => "pause",
+            Self::Slower => "slower",
+            Self::Faster => "faster",
+            Self::StepBackwards => "step-backwards",
+            Self::StepForwards => "step-forwards",
+            Self::SecondBackwards => "second-backwards",
+            Self::SecondForwards => "second-forwards",
+            Self::PlayForwards => "play-forwards",
+            Self::PlayBackwards => "play-backwards",
+            Self::FewerSeeds => "fewer-seeds",
+            Self::MoreSeeds => "more-seeds",
+            Self::NextSeedLayout => "next-seed-layout",
+            Self::NextSeedColors => "next-seed-colors",
+            Self::LessMutation => "less-mutation",
+            Self::MoreMutation => "more-mutation",
+            Self::WeakerTarget => "weaker-target",
+            Self::StrongerTarget => "stronger-target",
+            Self::ToggleLearnedMutation => "toggle-learned-mutation",
+            Self::NextBrushColor => "next-brush-color",
+            Self::Erase => "erase",
+            Self::PaintObstacles => "paint-obstacles",
+            Self::Fit => "fit",
+            Self::OneToOne => "one-to-one",
+            Self::SaveImage => "save-image",
+            Self::HighlightBorder => "highlight-border",
+            Self::Validate => "validate",
+            Self::TogglePanel => "toggle-panel",
+            Self::ToggleHud => "toggle-hud",
+            Self::ToggleInspector => "toggle-inspector",
+            Self::Record => "record",
+            Self::Help => "help",
+        }
+    }
+
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Reset => "reset",
+            Self::Pause => "pause",
+            Self::Slower => "slower",
+            Self::Faster => "faster",
+            Self::StepBackwards => "one step backwards",
+            Self::StepForwards => "one step forwards",
+            Self::SecondBackwards => "one second backwards",
+            Self::SecondForwards => "one second forwards",
+            Self::PlayForwards => "play the timeline forwards",
+            Self::PlayBackwards => "play the timeline backwards",
+            Self::FewerSeeds => "decrement number of initial seeds",
+            Self::MoreSeeds => "increment number of initial seeds",
+            Self::NextSeedLayout => "next seed layout",
+            Self::NextSeedColors => "next seed colors",
+            Self::LessMutation => "decrement max color mutation",
+            Self::MoreMutation => "increment max color mutation",
+            Self::WeakerTarget => "decrement target strength",
+            Self::StrongerTarget => "increment target strength",
+            Self::ToggleLearnedMutation => "toggle the learned mutation",
+            Self::NextBrushColor => "next brush color",
+            Self::Erase => "hold to erase with the mouse",
+            Self::PaintObstacles => "hold to paint obstacles with the left mouse",
+            Self::Fit => "fit the dish to the window",
+            Self::OneToOne => "one pixel per cell",
+            Self::SaveImage => "save image",
+            Self::HighlightBorder => "highlight border",
+            Self::Validate => "validate invariances",
+            Self::TogglePanel => "toggle the control panel",
+            Self::ToggleHud => "toggle the hud",
+            Self::ToggleInspector => "toggle showing the cell under the mouse",
+            Self::Record => "start or stop recording the growth",
+            Self::Help => "toggle this help",
+        }
+    }
+
+    fn default_bindings(self) -> Vec<Binding> {
+        const fn key(key: KeyboardKey) -> Binding {
+            Binding { key, shift: false }
+        }
+        match self {
+            Self::Reset => vec![key(KeyboardKey::KEY_SPACE)],
+            Self::Pause => vec![key(KeyboardKey::KEY_K)],
+            Self::Slower => vec![key(KeyboardKey::KEY_M)],
+            Self::Faster => vec![key(KeyboardKey::KEY_SLASH)],
+            Self::StepBackwards => vec![key(KeyboardKey::KEY_COMMA)],
+            Self::StepForwards => vec![key(KeyboardKey::KEY_PERIOD)],
+            Self::SecondBackwards => vec![key(KeyboardKey::KEY_J)],
+            Self::SecondForwards => vec![key(KeyboardKey::KEY_L)],
+            Self::PlayForwards => vec![key(KeyboardKey::KEY_R)],
+            Self::PlayBackwards => vec![Binding {
+                key: KeyboardKey::KEY_R,
+                shift: true,
+            }],
+            Self::FewerSeeds => vec![key(KeyboardKey::KEY_MINUS)],
+            Self::MoreSeeds => vec![key(KeyboardKey::KEY_EQUAL)],
+            Self::NextSeedLayout => vec![key(KeyboardKey::KEY_S)],
+            Self::NextSeedColors => vec![key(KeyboardKey::KEY_C)],
+            Self::LessMutation => vec![key(KeyboardKey::KEY_LEFT_BRACKET)],
+            Self::MoreMutation => vec![key(KeyboardKey::KEY_RIGHT_BRACKET)],
+            Self::WeakerTarget => vec![key(KeyboardKey::KEY_SEMICOLON)],
+            Self::StrongerTarget => vec![key(KeyboardKey::KEY_APOSTROPHE)],
+            Self::ToggleLearnedMutation => vec![key(KeyboardKey::KEY_X)],
+            Self::NextBrushColor => vec![key(KeyboardKey::KEY_N)],
+            Self::Erase => vec![key(KeyboardKey::KEY_E)],
+            Self::PaintObstacles => vec![key(KeyboardKey::KEY_O)],
+            Self::Fit => vec![key(KeyboardKey::KEY_F)],
+            Self::OneToOne => vec![key(KeyboardKey::KEY_ONE)],
+            Self::SaveImage => vec![key(KeyboardKey::KEY_P)],
+            Self::HighlightBorder => vec![key(KeyboardKey::KEY_B)],
+            Self::Validate => vec![key(KeyboardKey::KEY_V)],
+            Self::TogglePanel => vec![key(KeyboardKey::KEY_TAB)],
+            Self::ToggleHud => vec![key(KeyboardKey::KEY_I)],
+            Self::ToggleInspector => vec![key(KeyboardKey::KEY_T)],
+            Self::Record => vec![key(KeyboardKey::KEY_G)],
+            // `?` is shift + `/`
+            Self::Help => vec![
+                key(KeyboardKey::KEY_H),
+                Binding {
+                    key: KeyboardKey::KEY_SLASH,
+                    shift: true,
+                },
+            ],
+        }
+    }
+}
+
+/// the names of the keys in the config file
+const KEY_NAMES: [(&str, KeyboardKey); 67] = [
+    ("a", KeyboardKey::KEY_A),
+    ("b", KeyboardKey::KEY_B),
+    ("c", KeyboardKey::KEY_C),
+    ("d", KeyboardKey::KEY_D),
+    ("e", KeyboardKey::KEY_E),
+    ("f", KeyboardKey::KEY_F),
+    ("g", KeyboardKey::KEY_G),
+    ("h", KeyboardKey::KEY_H),
+    ("i", KeyboardKey::KEY_I),
+    ("j", KeyboardKey::KEY_J),
+    ("k", KeyboardKey::KEY_K),
+    ("l", KeyboardKey::KEY_L),
+    ("m", KeyboardKey::KEY_M),
+    ("n", KeyboardKey::KEY_N),
+    ("o", KeyboardKey::KEY_O),
+    ("p", KeyboardKey::KEY_P),
+    ("q", KeyboardKey::KEY_Q),
+    ("r", KeyboardKey::KEY_R),
+    ("s", KeyboardKey::KEY_S),
+    ("t", KeyboardKey::KEY_T),
+    ("u", KeyboardKey::KEY_U),
+    ("v", KeyboardKey::KEY_V),
+    ("w", KeyboardKey::KEY_W),
+    ("x", KeyboardKey::KEY_X),
+    ("y", KeyboardKey::KEY_Y),
+    ("z", KeyboardKey::KEY_Z),
+    ("0", KeyboardKey::KEY_ZERO),
+    ("1", KeyboardKey::KEY_ONE),
+    ("2", KeyboardKey::KEY_TWO),
+    ("3", KeyboardKey::KEY_THREE),
+    ("4", KeyboardKey::KEY_FOUR),
+    ("5", KeyboardKey::KEY_FIVE),
+    ("6", KeyboardKey::KEY_SIX),
+    ("7", KeyboardKey::KEY_SEVEN),
+    ("8", KeyboardKey::KEY_EIGHT),
+    ("9", KeyboardKey::KEY_NINE),
+    ("space", KeyboardKey::KEY_SPACE),
+    ("tab", KeyboardKey::KEY_TAB),
+    ("enter", KeyboardKey::KEY_ENTER),
+    ("backspace", KeyboardKey::KEY_BACKSPACE),
+    ("'", KeyboardKey::KEY_APOSTROPHE),
+    (",", KeyboardKey::KEY_COMMA),
+    ("-", KeyboardKey::KEY_MINUS),
+    (".", KeyboardKey::KEY_PERIOD),
+    ("/", KeyboardKey::KEY_SLASH),
+    (";", KeyboardKey::KEY_SEMICOLON),
+    ("=", KeyboardKey::KEY_EQUAL),
+    ("[", KeyboardKey::KEY_LEFT_BRACKET),
+    ("\\", KeyboardKey::KEY_BACKSLASH),
+    ("]", KeyboardKey::KEY_RIGHT_BRACKET),
+    ("`", KeyboardKey::KEY_GRAVE),
+    ("up", KeyboardKey::KEY_UP),
+    ("down", KeyboardKey::KEY_DOWN),
+    ("left", KeyboardKey::KEY_LEFT),
+    ("right", KeyboardKey::KEY_RIGHT),
+    ("f1", KeyboardKey::KEY_F1),
+    ("f2", KeyboardKey::KEY_F2),
+    ("f3", KeyboardKey::KEY_F3),
+    ("f4", KeyboardKey::KEY_F4),
+    ("f5", KeyboardKey::KEY_F5),
+    ("f6", KeyboardKey::KEY_F6),
+    ("f7", KeyboardKey::KEY_F7),
+    ("f8", KeyboardKey::KEY_F8),
+    ("f9", KeyboardKey::KEY_F9),
+    ("f10", KeyboardKey::KEY_F10),
+    ("f11", KeyboardKey::KEY_F11),
+    ("f12", KeyboardKey::KEY_F12),
+];
+
+/// a key, and whether shift has to be held with it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Binding {
+    key: KeyboardKey,
+    shift: bool,
+}
+
+impl Binding {
+    /// parses keys like `k`, `space`, or `shift+/`
+    fn parse(text: &str) -> Self {
+        let (shift, name) = text
+            .strip_prefix("shift+")
+            .map_or((false, text), |name| (true, name));
+        let key = KEY_NAMES
+            .iter()
+            .find(|(key_name, _)| *key_name == name)
+            .unwrap_or_else(|| panic!("unknown key {name}"))
+            .1;
+        Self { key, shift }
+    }
+
+    fn name(self) -> String {
+        let name = KEY_NAMES
+            .iter()
+            .find(|(_, key)| *key == self.key)
+            .unwrap()
+            .0;
+        if self.shift {
+            format!("shift+{name}")
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn shift_matches(self, rl: &RaylibHandle) -> bool {
+        let shift = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT)
+            || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+        shift == self.shift
+    }
+}
+
+/// the keys bound to each action
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    /// indexed by the action's position in `Action::ALL`
+    bindings: Vec<Vec<Binding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| action.default_bindings())
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// loads a config file with lines like `pause = k` or `help = h shift+/`,
+    /// where actions that aren't in the file keep their default keys
+    pub fn load(path: &std::path::Path) -> Self {
+        let mut keymap = Self::default();
+        for line in std::fs::read_to_string(path).unwrap().lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, keys) = line
+                .split_once('=')
+                .unwrap_or_else(|| panic!("expected `action = keys`, got {line}"));
+            let action = Action::ALL
+                .iter()
+                .position(|action| action.name() == name.trim())
+                .unwrap_or_else(|| panic!("unknown action {}", name.trim()));
+            keymap.bindings[action] = keys.split_whitespace().map(Binding::parse).collect();
+        }
+        let conflicts = keymap.conflicts();
+        assert!(
+            conflicts.is_empty(),
+            "keys bound to more than one action:\n{}",
+            conflicts.join("\n")
+        );
+        keymap
+    }
+
+    /// the config file with these keys
+    pub fn to_config(&self) -> String {
+        let mut config = String::from("# action = keys, separated by spaces\n");
+        for (action, line) in Action::ALL.iter().zip(self.lines()) {
+            writeln!(config, "{} = {line}", action.name()).unwrap();
+        }
+        config
+    }
+
+    /// a line for each binding that's used by more than one action
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for (i, bindings) in self.bindings.iter().enumerate() {
+            for binding in bindings {
+                for (j, bindings_1) in self.bindings.iter().enumerate().skip(i + 1) {
+                    if bindings_1.contains(binding) {
+                        conflicts.push(format!(
+                            "{} is bound to {} and {}",
+                            binding.name(),
+                            Action::ALL[i].name(),
+                            Action::ALL[j].name()
+                        ));
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    fn bindings(&self, action: Action) -> &[Binding] {
+        &self.bindings[Action::ALL.iter().position(|&a| a == action).unwrap()]
+    }
+
+    pub fn is_pressed(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| rl.is_key_pressed(binding.key) && binding.shift_matches(rl))
+    }
+
+    pub fn is_down(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| rl.is_key_down(binding.key) && binding.shift_matches(rl))
+    }
+
+    /// the keys of each action in the order of `Action::ALL`, like `h shift+/`
+    fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.bindings.iter().map(|bindings| {
+            bindings
+                .iter()
+                .map(|binding| binding.name())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+    }
+
+    /// the lines of the help, with the keys of each action
+    pub fn help(&self) -> Vec<String> {
+        Action::ALL
+            .iter()
+            .zip(self.lines())
+            .map(|(action, keys)| format!("{keys}: {}", action.description()))
+            .chain(
+                [
+                    "left mouse drag: paint seeds",
+                    "right mouse: pick the brush color from the dish",
+                    "mouse wheel: zoom",
+                    "middle mouse drag: pan",
+                ]
+                .map(String::from),
+            )
+            .collect()
+    }
+}
@@ -0,0 +1,40 @@
+use crate::my_rng::Rng;
+
+/// the color differences between neighboring pixels of an example image,
+/// so mutations have the same noise level and hue jitter as the example
+#[derive(Clone, Debug)]
+pub struct LearnedMutation {
+    differences: Vec<(i16, i16, i16)>,
+}
+
+impl LearnedMutation {
+    /// uses horizontally and vertically adjacent pixels that are both opaque
+    pub fn from_image(image: &image::RgbaImage) -> Self {
+        let mut differences = Vec::new();
+        for (x, y, pixel) in image.enumerate_pixels() {
+            for (x_1, y_1) in [(x + 1, y), (x, y + 1)] {
+                if x_1 >= image.width() || y_1 >= image.height() {
+                    continue;
+                }
+                let pixel_1 = image.get_pixel(x_1, y_1);
+                if pixel.0[3] < 128 || pixel_1.0[3] < 128 {
+                    continue;
+                }
+                let difference =
+                    |channel: usize| pixel_1.0[channel] as i16 - pixel.0[channel] as i16;
+                let (dr, dg, db) = (difference(0), difference(1), difference(2));
+                // both signs so colors don't drift
+                differences.extend([(dr, dg, db), (-dr, -dg, -db)]);
+            }
+        }
+        assert!(
+            !differences.is_empty(),
+            "the example needs two adjacent opaque pixels"
+        );
+        Self { differences }
+    }
+
+    pub fn sample(&self, rng: &mut Rng) -> (i16, i16, i16) {
+        self.differences[rng.next_u32_n(self.differences.len() as u32) as usize]
+    }
+}
@@ -0,0 +1,67 @@
+/// marks cells that were never filled, or filled without a parent or seed
+const NONE: u32 = u32::MAX;
+
+/// when each cell was filled, which cell it grew from, and which seed it descends from
+#[derive(Clone, Debug)]
+pub struct Lineage {
+    /// how many cells have been filled, counting seeds and cells filled again after being cleared
+    fill_count: u32,
+    fill_times: Vec<u32>,
+    parents: Vec<u32>,
+    seeds: Vec<u32>,
+}
+
+impl Lineage {
+    pub fn new(cell_count: usize) -> Self {
+        Self {
+            fill_count: 0,
+            fill_times: vec![NONE; cell_count],
+            parents: vec![NONE; cell_count],
+            seeds: vec![NONE; cell_count],
+        }
+    }
+
+    /// a seed is its own lineage seed and has no parent
+    pub fn record_seed(&mut self, cell: usize) {
+        self.record(cell, NONE, cell as u32);
+    }
+
+    pub fn record_growth(&mut self, parent: usize, cell: usize) {
+        self.record(cell, parent as u32, self.seeds[parent]);
+    }
+
+    fn record(&mut self, cell: usize, parent: u32, seed: u32) {
+        self.fill_times[cell] = self.fill_count;
+        self.parents[cell] = parent;
+        self.seeds[cell] = seed;
+        self.fill_count += 1;
+    }
+
+    /// forgets the cell, like when it's cleared or made an obstacle
+    pub fn forget(&mut self, cell: usize) {
+        self.fill_times[cell] = NONE;
+        self.parents[cell] = NONE;
+        self.seeds[cell] = NONE;
+    }
+
+    /// how many cells were filled before this one
+    pub fn fill_time(&self, cell: usize) -> Option<u32> {
+        some(self.fill_times[cell])
+    }
+
+    pub fn parent(&self, cell: usize) -> Option<usize> {
+        some(self.parents[cell]).map(|parent| parent as usize)
+    }
+
+    pub fn seed(&self, cell: usize) -> Option<usize> {
+        some(self.seeds[cell]).map(|seed| seed as usize)
+    }
+}
+
+const fn some(value: u32) -> Option<u32> {
+    if value == NONE {
+        None
+    } else {
+        Some(value)
+    }
+}
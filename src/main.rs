@@ -9,22 +9,76 @@
 #![allow(clippy::unreadable_literal)]
 
 mod bijective_finite_sequence;
+mod brush;
+mod control_panel;
+mod cube_map;
 mod dish;
+mod dish_texture;
+mod domain;
+mod grid;
+mod history;
+mod hud;
+mod inspector;
+mod keymap;
+mod learned_mutation;
+mod lineage;
+mod mutation_field;
 mod my_rng;
+mod neighborhood;
+mod obj;
+mod recorder;
+mod seed_colors;
+mod seed_layout;
+mod settings;
 mod state;
+mod topology;
+mod viewport;
+mod voxel;
+mod wind;
 
+use brush::BrushColor;
+use control_panel::{PanelAction, PANEL_WIDTH, TIMELINE_HEIGHT};
+use cube_map::CubeMap;
 use dish::Dish;
+use dish_texture::DishTexture;
+use domain::Domain;
+use grid::{Grid, Tiling};
+use history::{History, Timeline};
+use hud::StepRate;
+use keymap::{Action, Keymap};
+use learned_mutation::LearnedMutation;
+use mutation_field::MutationField;
 use my_rng::Rng;
+use neighborhood::Neighborhood;
+use obj::Obj;
 use raylib::prelude::*;
+use recorder::{Pacing, Recorder, FRAME_RATE};
+use seed_colors::{SeedColors, PALETTES};
+use seed_layout::SeedLayout;
+use settings::{Options, Settings};
 use state::State;
+use topology::Topology;
+use viewport::Viewport;
+use voxel::VoxelGrid;
+use wind::Wind;
 
+/// initial width and height of the window, which can be resized
 const SCREEN_SIZE: usize = 700;
+/// default side length of the square dish
+const DISH_SIZE: usize = 700;
+/// default side length of the hex dish
+const HEX_DISH_SIZE: usize = DISH_SIZE / 4;
+/// distance in cells between seeds painted with the mouse
+const SEED_BRUSH_SPACING: f32 = 3.0;
+/// radius in cells of the eraser
+const ERASER_RADIUS: f32 = 8.0;
+/// radius in cells of the brush for painting obstacles
+const OBSTACLE_BRUSH_RADIUS: f32 = 5.0;
 
 // TODO: something other than rgb
 // TODO: state as a newtype of rgba with a=0 => empty and a=255 => colored
-// TODO: click to insert a seed
-// TODO: zooming + pan + switch to egui/eframe
 
+#[allow(clippy::too_many_lines)]
 fn main() {
     // std::env::set_var("RUST_BACKTRACE", "1");
 
@@ -33,25 +87,440 @@ fn main() {
     // bench(&mut rng);
     // panic!("bench done");
 
-    // generate(5000, 8, 2, true);
+    // generate(Grid::new(5000, Tiling::Square, Neighborhood::von_neumann(1)), 8, 2, true);
 
-    run_raylib(&mut rng);
+    let mut tiling = Tiling::Square;
+    let mut size = None;
+    let mut neighborhood = None;
+    let mut mesh = None;
+    let mut voxels = None;
+    let mut cube_map = None;
+    let mut obstacles = None;
+    let mut domain = Domain::Full;
+    let mut seed_layout = SeedLayout::Uniform;
+    let mut seed_colors = SeedColors::Random;
+    let mut target = None;
+    let mut inpaint = None;
+    let mut mutation_example = None;
+    let mut mutation_field = None;
+    let mut wind = None;
+    let mut target_strength = 0.1;
+    let mut keymap = None;
+    let mut record_path = std::path::PathBuf::from("./recording.gif");
+    let mut frames = None;
+    let mut lineage = false;
+    let mut history = true;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--hex" => tiling = Tiling::Hex,
+            "--keymap" => {
+                keymap = Some(Keymap::load(std::path::Path::new(
+                    &args.next().expect("--keymap needs a path"),
+                )));
+            }
+            "--write-keymap" => {
+                let path = args.next().expect("--write-keymap needs a path");
+                let keymap = keymap.unwrap_or_default();
+                std::fs::write(path, keymap.to_config()).unwrap();
+                return;
+            }
+            "--seed" => {
+                rng = Rng::from_seed(args.next().expect("--seed needs a seed").parse().unwrap());
+            }
+            "--lineage" => lineage = true,
+            "--no-history" => history = false,
+            "--record" => record_path = args.next().expect("--record needs a path").into(),
+            "--frames" => {
+                frames = Some(Pacing::parse(
+                    &args.next().expect("--frames needs a number of steps"),
+                ));
+            }
+            "--size" => size = Some(args.next().expect("--size needs a size").parse().unwrap()),
+            "--mesh" => mesh = Some(args.next().expect("--mesh needs a path")),
+            "--cube-map" => {
+                cube_map = Some(
+                    args.next()
+                        .expect("--cube-map needs a size")
+                        .parse()
+                        .unwrap(),
+                );
+            }
+            "--voxels" => {
+                let size = args.next().expect("--voxels needs a size").parse().unwrap();
+                // checked before growing, which takes a while
+                assert!(
+                    size <= voxel::MAX_VOX_SIZE,
+                    "--voxels can be at most {}, the largest vox model",
+                    voxel::MAX_VOX_SIZE
+                );
+                voxels = Some(size);
+            }
+            "--obstacles" => obstacles = Some(args.next().expect("--obstacles needs a path")),
+            "--seed-layout" => {
+                seed_layout = parse_seed_layout(&args.next().expect("--seed-layout needs a value"));
+            }
+            "--seed-colors" => {
+                seed_colors = parse_seed_colors(&args.next().expect("--seed-colors needs a value"));
+            }
+            "--inpaint" => inpaint = Some(args.next().expect("--inpaint needs a path")),
+            "--mutation-example" => {
+                mutation_example = Some(args.next().expect("--mutation-example needs a path"));
+            }
+            "--mutation-field" => {
+                mutation_field = Some(parse_mutation_field(
+                    &args.next().expect("--mutation-field needs a value"),
+                ));
+            }
+            "--wind" => wind = Some(parse_wind(&args.next().expect("--wind needs a value"))),
+            "--target" => target = Some(args.next().expect("--target needs a path")),
+            "--target-strength" => {
+                target_strength = args
+                    .next()
+                    .expect("--target-strength needs a value")
+                    .parse()
+                    .unwrap();
+            }
+            "--domain" => domain = parse_domain(&args.next().expect("--domain needs a value")),
+            "--neighborhood" => {
+                neighborhood = Some(parse_neighborhood(
+                    &args.next().expect("--neighborhood needs a value"),
+                ));
+            }
+            _ => panic!("unknown argument {arg}"),
+        }
+    }
+    let neighborhood = neighborhood.unwrap_or_else(|| tiling.default_neighborhood());
+    assert!(
+        frames.is_none() || tiling == Tiling::Square,
+        "--frames can't be used with --hex, only square dishes can be recorded"
+    );
+
+    if let Some(mesh) = mesh {
+        generate_mesh(&mut rng, std::path::Path::new(&mesh), 8, 2);
+        return;
+    }
+    if let Some(size) = voxels {
+        generate_voxels(&mut rng, size, 8, 2);
+        return;
+    }
+    if let Some(size) = cube_map {
+        generate_cube_map(&mut rng, size, 8, 2);
+        return;
+    }
+
+    let inpaint = inpaint.map(|path| image::open(path).unwrap().into_rgba8());
+    let grid = Grid::new(
+        match (size, tiling, &inpaint) {
+            (Some(size), _, _) => size,
+            // one cell per pixel
+            (None, Tiling::Square, Some(image)) => image.width().max(image.height()) as usize,
+            (None, Tiling::Square, None) => DISH_SIZE,
+            (None, Tiling::Hex, _) => HEX_DISH_SIZE,
+        },
+        tiling,
+        neighborhood,
+    );
+    // dark pixels are obstacles
+    let obstacles = obstacles.map_or_else(
+        || vec![false; grid.cell_count()],
+        |path| {
+            grid.sample_image(&image::open(path).unwrap().into_rgba8())
+                .into_iter()
+                .map(|pixel| image::Pixel::to_luma(&pixel).0[0] < 128)
+                .collect()
+        },
+    );
+    // opaque pixels are filled and transparent pixels are the holes to grow into,
+    // and cells past the edge of a non-square image are outside, so saved images are cropped to it
+    let pixels: Vec<_> = match &inpaint {
+        None => vec![Some(image::Rgba([0, 0, 0, 0])); grid.cell_count()],
+        Some(image) if size.is_none() && tiling == Tiling::Square => (0..grid.cell_count())
+            .map(|cell| {
+                let (row, col) = grid.row_col(cell);
+                image.get_pixel_checked(col as u32, row as u32).copied()
+            })
+            .collect(),
+        Some(image) => {
+            assert_eq!(
+                image.width(),
+                image.height(),
+                "--inpaint images must be square with --size or --hex, since they're scaled to the dish"
+            );
+            grid.sample_image(image).into_iter().map(Some).collect()
+        }
+    };
+    let states = domain
+        .mask(&grid)
+        .into_iter()
+        .zip(obstacles)
+        .zip(pixels)
+        .map(
+            |((inside, obstacle), pixel)| match (inside, obstacle, pixel) {
+                (false, _, _) | (_, _, None) => State::Outside,
+                (true, true, _) => State::Obstacle,
+                (true, false, Some(image::Rgba([r, g, b, a]))) if a >= 128 => {
+                    State::Filled { r, g, b }
+                }
+                (true, false, Some(_)) => State::Empty,
+            },
+        )
+        .collect();
+
+    let target: Option<Vec<_>> = target.map(|path| {
+        grid.sample_image(&image::open(path).unwrap().into_rgba8())
+            .into_iter()
+            .map(|image::Rgba([r, g, b, _])| (r, g, b))
+            .collect()
+    });
+
+    let learned_mutation = mutation_example
+        .map(|path| LearnedMutation::from_image(&image::open(path).unwrap().into_rgba8()));
+    let mutation_strengths = mutation_field.map(|field| field.strengths(&mut rng, &grid));
+    let direction_weights = wind.map(|wind| wind.weights(&grid));
+
+    let options = Options {
+        initial_states: states,
+        seed_layout,
+        seed_colors,
+        target,
+        target_strength,
+        learned_mutation,
+        mutation_strengths,
+        direction_weights,
+        // the pixels around the holes are the seeds
+        seed_count: if inpaint.is_some() { 0 } else { 2 },
+        keymap: keymap.unwrap_or_else(|| {
+            let path = std::path::Path::new("./keymap.txt");
+            if path.exists() {
+                Keymap::load(path)
+            } else {
+                Keymap::default()
+            }
+        }),
+        record_path,
+        frames,
+        lineage,
+        history,
+    };
+    run_raylib(&mut rng, &grid, options);
+}
+
+/// parses `random`, `hues`, the name of a palette, a list of colors like `#ff8000,#0080ff`,
+/// or the path to an image
+fn parse_seed_colors(arg: &str) -> SeedColors {
+    if let Some((_, palette)) = PALETTES.iter().find(|(name, _)| *name == arg) {
+        return SeedColors::List(palette.to_vec());
+    }
+    match arg {
+        "random" => SeedColors::Random,
+        "hues" => SeedColors::Hues,
+        _ if arg.starts_with('#') || arg.contains(',') => SeedColors::parse_list(arg),
+        _ => SeedColors::Image(image::open(arg).unwrap().into_rgba8()),
+    }
+}
+
+/// parses `uniform`, `distinct`, `poisson`, `grid`, `ring`, `edge`, `center`,
+/// or the path to a csv or json file of points
+fn parse_seed_layout(arg: &str) -> SeedLayout {
+    match arg {
+        "uniform" => SeedLayout::Uniform,
+        "distinct" => SeedLayout::Distinct,
+        "poisson" => SeedLayout::PoissonDisk,
+        "grid" => SeedLayout::Grid,
+        "ring" => SeedLayout::Ring,
+        "edge" => SeedLayout::Edge,
+        "center" => SeedLayout::Center,
+        _ => SeedLayout::load_points(std::path::Path::new(arg)),
+    }
 }
 
-fn run_raylib(rng: &mut Rng) {
+/// parses `circle`, a polygon like `polygon:0.5,0;1,1;0,1`, or the path to an image
+fn parse_domain(arg: &str) -> Domain {
+    match arg.split_once(':') {
+        _ if arg == "circle" => Domain::Circle,
+        Some(("polygon", vertices)) => Domain::Polygon(
+            vertices
+                .split(';')
+                .map(|vertex| {
+                    let (x, y) = vertex
+                        .split_once(',')
+                        .unwrap_or_else(|| panic!("invalid domain {arg}"));
+                    (x.trim().parse().unwrap(), y.trim().parse().unwrap())
+                })
+                .collect(),
+        ),
+        _ => Domain::Image(image::open(arg).unwrap().into_rgba8()),
+    }
+}
+
+/// parses `radial`, `noise`, `noise:<scale>` with the scale from 0 to 1 across the dish,
+/// or the path to a grayscale image
+fn parse_mutation_field(arg: &str) -> MutationField {
+    match arg.split_once(':') {
+        _ if arg == "radial" => MutationField::Radial,
+        _ if arg == "noise" => MutationField::Noise(0.1),
+        Some(("noise", scale)) => MutationField::Noise(scale.parse().unwrap()),
+        _ => MutationField::Image(image::open(arg).unwrap().into_rgba8()),
+    }
+}
+
+/// parses `weights:<weight>,<weight>,...` with a weight for each direction of the neighborhood,
+/// `vector:<x>,<y>`, `spiral`, or the path to a vector field image
+fn parse_wind(arg: &str) -> Wind {
+    let parse_list = |list: &str| {
+        list.split(',')
+            .map(|word| word.trim().parse().unwrap())
+            .collect::<Vec<f32>>()
+    };
+    match arg.split_once(':') {
+        _ if arg == "spiral" => Wind::Spiral,
+        Some(("weights", weights)) => Wind::Weights(parse_list(weights)),
+        Some(("vector", vector)) => {
+            let &[x, y] = parse_list(vector).as_slice() else {
+                panic!("invalid wind {arg}")
+            };
+            Wind::Vector(x, y)
+        }
+        _ => Wind::Field(image::open(arg).unwrap().into_rgba8()),
+    }
+}
+
+/// parses `von-neumann`, `moore`, `knight`, `hex`, `von-neumann:<radius>`, `moore:<radius>`,
+/// or a list of offsets like `1,2;-2,1`
+fn parse_neighborhood(arg: &str) -> Neighborhood {
+    match arg.split_once(':') {
+        _ if arg == "von-neumann" => Neighborhood::von_neumann(1),
+        _ if arg == "moore" => Neighborhood::moore(1),
+        _ if arg == "knight" => Neighborhood::knight(),
+        _ if arg == "hex" => Neighborhood::hex(),
+        Some(("von-neumann", radius)) => Neighborhood::von_neumann(radius.parse().unwrap()),
+        Some(("moore", radius)) => Neighborhood::moore(radius.parse().unwrap()),
+        _ => Neighborhood::custom(
+            arg.split(';')
+                .map(|offset| {
+                    let (row_offset, col_offset) = offset
+                        .split_once(',')
+                        .unwrap_or_else(|| panic!("invalid neighborhood {arg}"));
+                    (
+                        row_offset.trim().parse().unwrap(),
+                        col_offset.trim().parse().unwrap(),
+                    )
+                })
+                .collect(),
+        ),
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+/// opens the window and grows dishes from the options until it's closed
+fn run_raylib(rng: &mut Rng, grid: &Grid, options: Options) {
+    let Options {
+        mut initial_states,
+        seed_layout,
+        seed_colors,
+        target,
+        target_strength,
+        learned_mutation,
+        mutation_strengths,
+        direction_weights,
+        seed_count,
+        keymap,
+        record_path,
+        frames,
+        lineage,
+        history,
+    } = options;
     let (mut rl, thread) = raylib::init()
         .size(SCREEN_SIZE as i32, SCREEN_SIZE as i32)
         .title("spore")
+        .resizable()
         .build();
 
-    let mut paused = false;
-    // how much the radius (in units of pixels) of the colored region should grow per second
-    let mut radius_per_second: f32 = 32.0;
-    // how many pixels are colored initially
-    let mut seed_count: usize = 2;
-    let mut color_step: i32 = 3;
-    let mut highlight_border = true;
-    let mut dish = Dish::from_seed_count(rng, SCREEN_SIZE, seed_count);
+    // the layouts and colors that can be cycled through, including the ones from the command line
+    let mut seed_layouts = SeedLayout::ALL.to_vec();
+    if !seed_layouts.contains(&seed_layout) {
+        seed_layouts.push(seed_layout.clone());
+    }
+    let mut seed_colors_options = SeedColors::options();
+    if !seed_colors_options.contains(&seed_colors) {
+        seed_colors_options.push(seed_colors.clone());
+    }
+    let mut settings = Settings {
+        paused: false,
+        radius_per_second: 32.0,
+        seed_count,
+        color_step: 3,
+        highlight_border: true,
+        seed_layout,
+        seed_colors,
+        brush_color: BrushColor::Random,
+        picked_color: (128, 128, 128),
+        target_strength: target.as_ref().map(|_| target_strength),
+        use_learned_mutation: learned_mutation.as_ref().map(|_| true),
+        show_panel: true,
+        show_hud: false,
+        show_inspector: false,
+        show_help: false,
+    };
+    let mut viewport = Viewport::fit(grid, SCREEN_SIZE as f32, SCREEN_SIZE as f32);
+    let mut last_mouse = Vector2::zero();
+    // where the seed brush stroke continues from, while it's being painted
+    let mut stroke_point = None;
+    // where the eraser stroke continues from, while it's being erased
+    let mut erase_point = None;
+    // the corner where the rectangle being erased was started
+    let mut erase_rect_corner = None;
+    let mut window_size = (SCREEN_SIZE as f32, SCREEN_SIZE as f32);
+    // hex dishes are drawn a hexagon at a time
+    let mut dish_texture =
+        (grid.tiling() == Tiling::Square).then(|| DishTexture::new(&mut rl, &thread, grid));
+    let new_dish = |rng: &mut Rng, states: &[State], settings: &Settings, seed_count| {
+        let mut dish = Dish::from_states(grid.clone(), states.to_vec());
+        if lineage {
+            dish.record_lineage();
+        }
+        if history {
+            dish.record_history();
+        }
+        if let (Some(target), Some(target_strength)) = (&target, settings.target_strength) {
+            dish.set_target(target.clone(), target_strength);
+        }
+        if settings.use_learned_mutation == Some(true) {
+            dish.set_learned_mutation(learned_mutation.clone());
+        }
+        if let Some(mutation_strengths) = &mutation_strengths {
+            dish.set_mutation_strengths(mutation_strengths.clone());
+        }
+        if let Some(direction_weights) = &direction_weights {
+            dish.set_direction_weights(direction_weights.clone());
+        }
+        let cells = settings
+            .seed_layout
+            .cells(rng, grid, dish.states(), seed_count);
+        for (cell, state) in cells
+            .iter()
+            .zip(settings.seed_colors.colors(rng, grid, &cells))
+        {
+            dish.insert_seed(*cell, state);
+        }
+        dish
+    };
+    let mut dish = new_dish(rng, &initial_states, &settings, settings.seed_count);
+    let mut panel_action = None;
+    let mut step_rate = StepRate::default();
+    // replays the history while the dish is paused or done
+    let mut timeline: Option<Timeline> = None;
+    // whether the timeline was shown instead of the dish last frame
+    let mut was_replaying = false;
+    // 1 to play the timeline forwards, -1 to play it backwards
+    let mut playback = 0;
+    // records from the start with `--frames`
+    let mut recorder = frames.map(|pacing| {
+        let mut recorder = Recorder::new(&record_path, pacing);
+        recorder.record_frame(&dish);
+        recorder
+    });
     while !rl.window_should_close() {
         // assert!(rl.get_time() < 10.0);
         // assert!(!dish.is_done());
@@ -59,86 +528,351 @@ fn run_raylib(rng: &mut Rng) {
         let dt = rl.get_frame_time().min(1.0 / 30.0);
         // println!("dt: {dt}");
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_SPACE) {
-            dish = Dish::from_seed_count(rng, SCREEN_SIZE, seed_count);
+        if keymap.is_pressed(&rl, Action::TogglePanel) {
+            settings.show_panel = !settings.show_panel;
+        }
+        if keymap.is_pressed(&rl, Action::ToggleHud) {
+            settings.show_hud = !settings.show_hud;
+        }
+        if keymap.is_pressed(&rl, Action::Record) {
+            if let Some(recorder) = recorder.take() {
+                recorder.finish(&dish);
+                println!("saved recording to {}", record_path.display());
+            } else if grid.tiling() == Tiling::Square {
+                // a frame for each frame of the window at the current speed
+                let pacing = frames.unwrap_or_else(|| {
+                    Pacing::Radius(settings.radius_per_second / f32::from(FRAME_RATE))
+                });
+                let mut recorder_1 = Recorder::new(&record_path, pacing);
+                recorder_1.record_frame(&dish);
+                recorder = Some(recorder_1);
+            } else {
+                println!("only square dishes can be recorded");
+            }
+        }
+        if keymap.is_pressed(&rl, Action::ToggleInspector) {
+            settings.show_inspector = !settings.show_inspector;
+        }
+        if keymap.is_pressed(&rl, Action::Help) {
+            settings.show_help = !settings.show_help;
+        }
+
+        if keymap.is_pressed(&rl, Action::Reset) {
+            panel_action = Some(PanelAction::Reset);
+        }
+        match panel_action.take() {
+            Some(PanelAction::Reset) => {
+                dish = new_dish(rng, &initial_states, &settings, settings.seed_count);
+            }
+            Some(PanelAction::ExportImage) => {
+                dish.save_to_image(
+                    std::path::Path::new("./image.png"),
+                    settings.highlight_border,
+                );
+            }
+            Some(PanelAction::SaveDish) => dish.save_states(std::path::Path::new("./dish.png")),
+            Some(PanelAction::LoadDish) => {
+                match Dish::load_states(grid, std::path::Path::new("./dish.png")) {
+                    Ok(states) => dish = new_dish(rng, &states, &settings, 0),
+                    Err(error) => println!("couldn't load ./dish.png: {error}"),
+                }
+            }
+            None => {}
         }
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_K) {
-            paused = !paused;
+        if keymap.is_pressed(&rl, Action::Pause) {
+            settings.paused = !settings.paused;
         }
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_M) {
-            radius_per_second = (0.5 * radius_per_second).max(2e-16);
+        if keymap.is_pressed(&rl, Action::Slower) {
+            settings.radius_per_second = (0.5 * settings.radius_per_second).max(2e-16);
         }
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_SLASH) {
-            radius_per_second = (2.0 * radius_per_second).min(2e16);
+        if keymap.is_pressed(&rl, Action::Faster) {
+            settings.radius_per_second = (2.0 * settings.radius_per_second).min(2e16);
         }
-        let steps_per_second = dish.perimeter() * radius_per_second;
+        let steps_per_second = dish.perimeter() * settings.radius_per_second;
 
-        if rl.is_key_down(raylib::consts::KeyboardKey::KEY_COMMA) {
-            println!("todo: one step backwards");
+        // going backwards pauses to show the timeline
+        if keymap.is_down(&rl, Action::StepBackwards)
+            || keymap.is_pressed(&rl, Action::SecondBackwards)
+        {
+            settings.paused = true;
         }
-        if rl.is_key_down(raylib::consts::KeyboardKey::KEY_PERIOD) {
-            while !dish.maybe_step(rng, color_step) {}
+        if keymap.is_pressed(&rl, Action::PlayForwards) {
+            settings.paused = true;
+            playback = isize::from(playback != 1);
+        }
+        if keymap.is_pressed(&rl, Action::PlayBackwards) {
+            settings.paused = true;
+            playback = -isize::from(playback != -1);
+        }
+        if settings.paused || dish.is_done() {
+            if timeline.is_none() && dish.history().is_some() {
+                timeline = Some(Timeline::new(&dish));
+            }
+        } else {
+            timeline = None;
+            playback = 0;
+        }
+        // whether the timeline is before the end, so stepping forwards replays it instead of growing the dish
+        let mut replaying = false;
+        if let (Some(timeline), Some(history)) = (&mut timeline, dish.history()) {
+            // replays at the same speed the dish grew
+            #[expect(clippy::cast_possible_truncation)]
+            let changes_per_second =
+                (timeline.dish().perimeter() * settings.radius_per_second).max(1.0) as isize;
+            if keymap.is_down(&rl, Action::StepBackwards) {
+                timeline.seek_by(history, -1);
+            }
+            if keymap.is_pressed(&rl, Action::SecondBackwards) {
+                timeline.seek_by(history, -changes_per_second);
+            }
+            replaying = timeline.position() < history.len();
+            if replaying && keymap.is_down(&rl, Action::StepForwards) {
+                timeline.seek_by(history, 1);
+            }
+            if replaying && keymap.is_pressed(&rl, Action::SecondForwards) {
+                timeline.seek_by(history, changes_per_second);
+            }
+            if playback != 0 {
+                #[expect(clippy::cast_possible_truncation)]
+                let changes = ((changes_per_second as f32 * dt) as isize).max(1);
+                timeline.seek_by(history, playback * changes);
+                if timeline.position() == 0 || timeline.position() == history.len() {
+                    playback = 0;
+                }
+            }
         }
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_J) {
-            println!("todo: one second backwards");
+        if !replaying && keymap.is_down(&rl, Action::StepForwards) {
+            // a done dish has nowhere to step
+            while !dish.is_done()
+                && !maybe_step(
+                    &mut dish,
+                    rng,
+                    settings.color_step,
+                    &mut step_rate,
+                    recorder.as_mut(),
+                )
+            {}
         }
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_L) {
+
+        if !replaying && keymap.is_pressed(&rl, Action::SecondForwards) {
             let mut step_count = 0;
             while step_count < steps_per_second as usize && !dish.is_done() {
-                step_count += dish.maybe_step(rng, color_step) as usize;
+                step_count += maybe_step(
+                    &mut dish,
+                    rng,
+                    settings.color_step,
+                    &mut step_rate,
+                    recorder.as_mut(),
+                ) as usize;
             }
         }
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_MINUS) {
-            seed_count = seed_count.saturating_sub(1);
+        if keymap.is_pressed(&rl, Action::FewerSeeds) {
+            settings.seed_count = settings.seed_count.saturating_sub(1);
         }
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_EQUAL) {
-            seed_count += 1;
+        if keymap.is_pressed(&rl, Action::MoreSeeds) {
+            settings.seed_count += 1;
             // loop {
-            //     let row = rng.next_u32_n(SCREEN_SIZE as u32) as usize;
-            //     let col = rng.next_u32_n(SCREEN_SIZE as u32) as usize;
-            //     if matches!(dish.as_slice()[row][col], State::Empty) {
-            //         dish.insert_seed(row, col, State::random_filled(rng));
+            //     let row = rng.next_u32_n(DISH_SIZE as u32) as usize;
+            //     let col = rng.next_u32_n(DISH_SIZE as u32) as usize;
+            //     if matches!(dish.states()[grid.cell(row, col)], State::Empty) {
+            //         dish.insert_seed(grid.cell(row, col), State::random_filled(rng));
             //         break;
             //     }
             // }
         }
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_LEFT_BRACKET) {
-            color_step = (color_step - 1).max(0);
+        if keymap.is_pressed(&rl, Action::NextSeedLayout) {
+            settings.seed_layout = settings::next(&seed_layouts, &settings.seed_layout);
+        }
+
+        if keymap.is_pressed(&rl, Action::NextSeedColors) {
+            settings.seed_colors = settings::next(&seed_colors_options, &settings.seed_colors);
+        }
+
+        if keymap.is_pressed(&rl, Action::LessMutation) {
+            settings.color_step = (settings.color_step - 1).max(0);
+        }
+        if keymap.is_pressed(&rl, Action::MoreMutation) {
+            settings.color_step += 1;
+        }
+
+        if let Some(target_strength) = &mut settings.target_strength {
+            if keymap.is_pressed(&rl, Action::WeakerTarget) {
+                *target_strength = (*target_strength - 0.05).max(0.0);
+            }
+            if keymap.is_pressed(&rl, Action::StrongerTarget) {
+                *target_strength = (*target_strength + 0.05).min(1.0);
+            }
         }
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_RIGHT_BRACKET) {
-            color_step += 1;
+
+        if let Some(use_learned_mutation) = &mut settings.use_learned_mutation {
+            if keymap.is_pressed(&rl, Action::ToggleLearnedMutation) {
+                *use_learned_mutation = !*use_learned_mutation;
+            }
         }
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_P) {
-            dish.save_to_image(std::path::Path::new("./image.png"), highlight_border);
+        if keymap.is_pressed(&rl, Action::SaveImage) {
+            panel_action = Some(PanelAction::ExportImage);
         }
 
-        if rl.is_key_pressed(raylib::consts::KeyboardKey::KEY_B) {
-            highlight_border = !highlight_border;
+        if keymap.is_pressed(&rl, Action::HighlightBorder) {
+            settings.highlight_border = !settings.highlight_border;
         }
 
-        if rl.is_key_down(raylib::consts::KeyboardKey::KEY_V) {
+        if keymap.is_down(&rl, Action::Validate) {
             dish.validate();
         }
 
-        if rl.is_mouse_button_pressed(MouseButton::MOUSE_LEFT_BUTTON)
-            && ((0..SCREEN_SIZE as i32).contains(&rl.get_mouse_x())
-                && !(0..SCREEN_SIZE as i32).contains(&rl.get_mouse_y()))
+        if rl.is_window_resized() {
+            // keep the same part of the dish in the center
+            let (screen_width, screen_height) =
+                (rl.get_screen_width() as f32, rl.get_screen_height() as f32);
+            viewport.pan(
+                (screen_width - window_size.0) / 2.0,
+                (screen_height - window_size.1) / 2.0,
+            );
+            window_size = (screen_width, screen_height);
+        }
+
+        let mouse = rl.get_mouse_position();
+        let (mouse_x, mouse_y) = viewport.to_grid(mouse.x, mouse.y);
+        // the mouse only acts on the dish when it's not over the panel
+        let over_dish = (!settings.show_panel
+            || mouse.x < rl.get_screen_width() as f32 - PANEL_WIDTH)
+            && (timeline.is_none() || mouse.y < rl.get_screen_height() as f32 - TIMELINE_HEIGHT);
+
+        let wheel_move = rl.get_mouse_wheel_move();
+        if wheel_move != 0.0 && over_dish {
+            viewport.zoom(wheel_move, mouse.x, mouse.y);
+        }
+        if rl.is_mouse_button_down(MouseButton::MOUSE_MIDDLE_BUTTON) {
+            viewport.pan(mouse.x - last_mouse.x, mouse.y - last_mouse.y);
+        }
+        last_mouse = mouse;
+        if keymap.is_pressed(&rl, Action::Fit) {
+            viewport = Viewport::fit(
+                grid,
+                rl.get_screen_width() as f32,
+                rl.get_screen_height() as f32,
+            );
+        }
+        if keymap.is_pressed(&rl, Action::OneToOne) {
+            viewport = Viewport::one_to_one(
+                grid,
+                rl.get_screen_width() as f32,
+                rl.get_screen_height() as f32,
+            );
+        }
+
+        if keymap.is_pressed(&rl, Action::NextBrushColor) {
+            let (r, g, b) = settings.picked_color;
+            settings.brush_color = match settings.brush_color {
+                BrushColor::Random => BrushColor::Fixed(r, g, b),
+                BrushColor::Fixed(..) => BrushColor::Sampled,
+                BrushColor::Sampled => BrushColor::Random,
+            };
+        }
+        if over_dish
+            && rl.is_mouse_button_pressed(MouseButton::MOUSE_RIGHT_BUTTON)
+            && !keymap.is_down(&rl, Action::Erase)
+        {
+            if let Some(State::Filled { r, g, b }) = grid
+                .cell_at(mouse_x, mouse_y)
+                .map(|cell| dish.states()[cell])
+            {
+                settings.picked_color = (r, g, b);
+                settings.brush_color = BrushColor::Fixed(r, g, b);
+            }
+        }
+
+        // erases along the stroke, or the rectangle dragged out with the right mouse button
+        let erasing = keymap.is_down(&rl, Action::Erase);
+        if over_dish && erasing && rl.is_mouse_button_down(MouseButton::MOUSE_LEFT_BUTTON) {
+            let from = erase_point.unwrap_or((mouse_x, mouse_y));
+            let (mut points, last) = brush::stroke(from, (mouse_x, mouse_y), ERASER_RADIUS / 2.0);
+            points.push(from);
+            erase_point = Some(last);
+            for (x, y) in points {
+                dish.clear_region(grid.cells_within(x, y, ERASER_RADIUS));
+            }
+        } else {
+            erase_point = None;
+        }
+        if over_dish && erasing && rl.is_mouse_button_pressed(MouseButton::MOUSE_RIGHT_BUTTON) {
+            erase_rect_corner = Some((mouse_x, mouse_y));
+        }
+        if rl.is_mouse_button_released(MouseButton::MOUSE_RIGHT_BUTTON) {
+            if let Some((x, y)) = erase_rect_corner.take() {
+                dish.clear_region(grid.cells_inside_rect(
+                    x.min(mouse_x),
+                    y.min(mouse_y),
+                    x.max(mouse_x),
+                    y.max(mouse_y),
+                ));
+            }
+        }
+
+        // paints seeds along the stroke
+        if over_dish
+            && rl.is_mouse_button_down(MouseButton::MOUSE_LEFT_BUTTON)
+            && !keymap.is_down(&rl, Action::PaintObstacles)
+            && !erasing
+        {
+            let points = match stroke_point {
+                None => {
+                    stroke_point = Some((mouse_x, mouse_y));
+                    vec![(mouse_x, mouse_y)]
+                }
+                Some(from) => {
+                    let (points, last) =
+                        brush::stroke(from, (mouse_x, mouse_y), SEED_BRUSH_SPACING);
+                    stroke_point = Some(last);
+                    points
+                }
+            };
+            for (x, y) in points {
+                if let Some(cell) = grid.cell_at(x, y) {
+                    let state = settings
+                        .brush_color
+                        .state(rng, grid, &settings.seed_colors, cell);
+                    dish.insert_seed(cell, state);
+                }
+            }
+        } else {
+            stroke_point = None;
+        }
+
+        if over_dish
+            && keymap.is_down(&rl, Action::PaintObstacles)
+            && rl.is_mouse_button_down(MouseButton::MOUSE_LEFT_BUTTON)
         {
-            dish.insert_seed(
-                rl.get_mouse_y() as usize,
-                rl.get_mouse_x() as usize,
-                State::random_filled(rng),
+            for cell in grid.cells_within(mouse_x, mouse_y, OBSTACLE_BRUSH_RADIUS) {
+                if !matches!(initial_states[cell], State::Outside) {
+                    initial_states[cell] = State::Obstacle;
+                }
+                dish.insert_obstacle(cell);
+            }
+        }
+
+        // the settings that are part of the dish
+        if let Some(target_strength) = settings.target_strength {
+            dish.set_target_strength(target_strength);
+        }
+        if let Some(use_learned_mutation) = settings.use_learned_mutation {
+            dish.set_learned_mutation(
+                learned_mutation
+                    .as_ref()
+                    .filter(|_| use_learned_mutation)
+                    .cloned(),
             );
         }
 
         // step the dish
-        if !paused && !dish.is_done() {
+        if !settings.paused && !dish.is_done() {
             // find steps_per_second = d/dt (area) from radius_per_second = d/dt (radius)
             // suppose the filled region is a circle (the pi's cancel in the end so its actually invariant to shape)
             // radius = perimeter / (2 * pi)
@@ -146,25 +880,159 @@ fn run_raylib(rng: &mut Rng) {
             // d/dt (area) = d/dt (pi * radius^2)
             // d/dt (area) = 2 * pi * radius * d/dt (radius)
             // d/dt (area) = perimeter * d/dt (radius)
+            // direction weights average 1 at each cell, so with wind this is the radius growing at the average speed.
+            // steps against the wind are rejected more often, but only taken steps are counted
             #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
             let target_step_count = ((steps_per_second * dt) as usize).max(1);
             let mut step_count = 0;
             while step_count < target_step_count && !dish.is_done() {
-                step_count += dish.maybe_step(rng, color_step) as usize;
+                step_count += maybe_step(
+                    &mut dish,
+                    rng,
+                    settings.color_step,
+                    &mut step_rate,
+                    recorder.as_mut(),
+                ) as usize;
+            }
+        }
+
+        if dish.is_done() {
+            if let Some(recorder) = recorder.take() {
+                recorder.finish(&dish);
+                println!("saved recording to {}", record_path.display());
+            }
+        }
+
+        // the timeline follows the dish while it's at the end, and starts over if the dish was changed while replaying
+        if let (Some(timeline_1), Some(history)) = (&mut timeline, dish.history()) {
+            if !timeline_1.catch_up(history) {
+                timeline = Some(Timeline::new(&dish));
+                playback = 0;
+            }
+        }
+        let history_len = dish.history().map_or(0, History::len);
+        let replaying = timeline
+            .as_ref()
+            .is_some_and(|timeline| timeline.position() < history_len);
+        if let Some(dish_texture) = &mut dish_texture {
+            let shown = match &mut timeline {
+                Some(timeline) if replaying => timeline.dish_mut(),
+                _ => &mut dish,
+            };
+            if replaying == was_replaying {
+                dish_texture.update(shown);
+            } else {
+                dish_texture.redraw(shown);
             }
         }
+        was_replaying = replaying;
 
+        step_rate.update(rl.get_frame_time());
+
+        let fps = rl.get_fps();
         let mut draw_handle: RaylibDrawHandle = rl.begin_drawing(&thread);
         draw_handle.clear_background(Color::BLACK);
-        dish.draw(&mut draw_handle, highlight_border);
+        let shown = match &timeline {
+            Some(timeline) if replaying => timeline.dish(),
+            _ => &dish,
+        };
+        match &mut dish_texture {
+            Some(dish_texture) => {
+                dish_texture.draw(&mut draw_handle, &thread, viewport);
+                if settings.highlight_border {
+                    shown.draw_border(&mut draw_handle, viewport);
+                }
+            }
+            None => shown.draw(&mut draw_handle, settings.highlight_border, viewport),
+        }
+        let mut stats = vec![
+            format!(
+                "filled: {:.1}%",
+                100.0 * dish.filled_count() as f32 / dish.states().len() as f32
+            ),
+            format!("border: {}", dish.perimeter()),
+            format!(
+                "steps per second: {:.0} / {steps_per_second:.0}",
+                step_rate.steps_per_second()
+            ),
+            format!("failed steps: {:.1}%", 100.0 * step_rate.failure_ratio()),
+            format!("fps: {fps}"),
+        ];
+        if let Some(recorder) = &recorder {
+            stats.push(format!("recording: {} frames", recorder.frame_count()));
+        }
+        if settings.show_hud {
+            let parameters = [
+                format!("seeds: {}", settings.seed_count),
+                format!("color step: {}", settings.color_step),
+                format!("radius per second: {}", settings.radius_per_second),
+                format!("rng seed: {}", rng.initial_seed()),
+            ];
+            hud::draw(&mut draw_handle, &[&parameters[..], &stats[..]].concat());
+        }
+        if settings.show_panel {
+            panel_action = control_panel::draw(
+                &mut draw_handle,
+                &mut settings,
+                &seed_layouts,
+                &seed_colors_options,
+                &stats,
+            );
+        }
+        if settings.show_inspector && over_dish {
+            if let Some(cell) = grid.cell_at(mouse_x, mouse_y) {
+                hud::draw_at(
+                    &mut draw_handle,
+                    (mouse.x as i32, mouse.y as i32),
+                    // the timeline's copy doesn't record lineage
+                    &inspector::lines(shown, dish.lineage(), cell),
+                );
+            }
+        }
+        if let (Some(timeline), Some(history)) = (&mut timeline, dish.history()) {
+            if let Some(position) = control_panel::draw_timeline(
+                &mut draw_handle,
+                timeline.position(),
+                history.len(),
+                settings.show_panel,
+            ) {
+                timeline.seek(history, position);
+                playback = 0;
+            }
+        }
+        if settings.show_help {
+            hud::draw_help(&mut draw_handle, &keymap.help());
+        }
+    }
+    // closing the window finishes the recording so far
+    if let Some(recorder) = recorder {
+        recorder.finish(&dish);
+        println!("saved recording to {}", record_path.display());
+    }
+}
+
+/// tries to take a step, counting it in the stats and recording it
+fn maybe_step(
+    dish: &mut Dish<Grid>,
+    rng: &mut Rng,
+    color_step: i32,
+    step_rate: &mut StepRate,
+    recorder: Option<&mut Recorder>,
+) -> bool {
+    let taken = dish.maybe_step(rng, color_step);
+    step_rate.record(taken);
+    if let (true, Some(recorder)) = (taken, recorder) {
+        recorder.step(dish);
     }
+    taken
 }
 
 fn bench(rng: &mut Rng) {
     for size_mul in 1..20 {
         let size = size_mul * 100;
-        let mut dish = Dish::new(size);
-        dish.insert_seed(size / 2, size / 2, State::random_filled(rng));
+        let grid = Grid::new(size, Tiling::Square, Neighborhood::von_neumann(1));
+        let mut dish = Dish::new(grid.clone());
+        dish.insert_seed(grid.cell(size / 2, size / 2), State::random_filled(rng));
 
         let start = std::time::Instant::now();
         while !dish.is_done() {
@@ -177,18 +1045,52 @@ fn bench(rng: &mut Rng) {
 }
 
 /// saves an image with these parameters
-fn generate(size: usize, seed_count: usize, color_step: i32, highlight_border: bool) {
+fn generate(grid: Grid, seed_count: usize, color_step: i32, highlight_border: bool) {
     let start = std::time::Instant::now();
     let mut rng = Rng::seeded();
-    let mut dish = Dish::from_seed_count(&mut rng, size, seed_count);
+    let mut dish = Dish::from_seed_count(&mut rng, grid, seed_count);
     while !dish.is_done() {
         dish.maybe_step(&mut rng, color_step);
     }
     dish.save_to_image(std::path::Path::new("./image.png"), highlight_border);
     let elapsed = start.elapsed();
     println!(
-        "generated dish with size {} in time {}s",
-        size,
+        "generated dish with {} cells in time {}s",
+        dish.states().len(),
         elapsed.as_secs_f32()
     );
 }
+
+/// grows on the vertices of an obj mesh and saves it with vertex colors
+fn generate_mesh(rng: &mut Rng, path: &std::path::Path, seed_count: usize, color_step: i32) {
+    let obj = Obj::load(path);
+    let mut dish = Dish::from_seed_count(rng, obj.graph(), seed_count);
+    while !dish.is_done() {
+        dish.maybe_step(rng, color_step);
+    }
+    obj.save_with_colors(std::path::Path::new("./mesh.obj"), &dish);
+}
+
+/// grows in a cube of voxels and saves it as `./voxels.vox` and as slices in `./slices/`
+fn generate_voxels(rng: &mut Rng, size: usize, seed_count: usize, color_step: i32) {
+    let mut dish = Dish::from_seed_count(rng, VoxelGrid::new(size, size, size), seed_count);
+    while !dish.is_done() {
+        dish.maybe_step(rng, color_step);
+    }
+    dish.save_to_vox(std::path::Path::new("./voxels.vox"));
+    dish.save_slices(std::path::Path::new("./slices"));
+}
+
+/// grows on the surface of a cube and saves the faces in `./cube_map/`
+/// and the equirectangular projection as `./equirectangular.png`
+fn generate_cube_map(rng: &mut Rng, size: usize, seed_count: usize, color_step: i32) {
+    let mut dish = Dish::from_seed_count(rng, CubeMap::new(size), seed_count);
+    while !dish.is_done() {
+        dish.maybe_step(rng, color_step);
+    }
+    dish.save_faces(std::path::Path::new("./cube_map"));
+    dish.save_equirectangular(
+        std::path::Path::new("./equirectangular.png"),
+        4 * size as u32,
+    );
+}
@@ -0,0 +1,54 @@
+use crate::grid::Grid;
+use crate::my_rng::Rng;
+use crate::topology::Topology;
+
+/// how strongly each cell mutates, from 0 (not at all) to 1 (the full color step)
+#[derive(Clone, Debug)]
+pub enum MutationField {
+    /// calm in the center and strongest in the corners
+    Radial,
+    /// smooth random noise with features about this far apart
+    Noise(f32),
+    /// the brightness of an image stretched over the grid
+    Image(image::RgbaImage),
+}
+
+impl MutationField {
+    /// the strength at the center of each cell
+    pub fn strengths(&self, rng: &mut Rng, grid: &Grid) -> Vec<f32> {
+        let points = (0..grid.cell_count()).map(|cell| grid.unit_center(cell));
+        match self {
+            Self::Radial => points
+                .map(|(x, y)| ((x - 0.5).hypot(y - 0.5) / 0.5f32.hypot(0.5)).min(1.0))
+                .collect(),
+            Self::Noise(scale) => {
+                // value noise: random values on a lattice, smoothly interpolated
+                let lattice_size = (1.0 / scale).ceil() as usize + 2;
+                let lattice = (0..lattice_size * lattice_size)
+                    .map(|_| rng.next_u32_n(1024) as f32 / 1023.0)
+                    .collect::<Vec<_>>();
+                let smoothstep = |t: f32| t * t * 2.0f32.mul_add(-t, 3.0);
+                points
+                    .map(|(x, y)| {
+                        let (x, y) = (x / scale, y / scale);
+                        let (col, row) = (x as usize, y as usize);
+                        let (t_x, t_y) = (smoothstep(x.fract()), smoothstep(y.fract()));
+                        let value = |row: usize, col: usize| lattice[row * lattice_size + col];
+                        let top =
+                            t_x.mul_add(value(row, col + 1) - value(row, col), value(row, col));
+                        let bottom = t_x.mul_add(
+                            value(row + 1, col + 1) - value(row + 1, col),
+                            value(row + 1, col),
+                        );
+                        t_y.mul_add(bottom - top, top)
+                    })
+                    .collect()
+            }
+            Self::Image(image) => grid
+                .sample_image(image)
+                .into_iter()
+                .map(|pixel| image::Pixel::to_luma(&pixel).0[0] as f32 / 255.0)
+                .collect(),
+        }
+    }
+}
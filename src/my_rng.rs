@@ -7,18 +7,31 @@
 // generally try to take higher bits of the seed
 pub struct Rng {
     seed: u64,
+    initial_seed: u64,
 }
 
 impl Rng {
     pub fn seeded() -> Self {
-        Self {
-            seed: std::time::SystemTime::now()
+        Self::from_seed(
+            std::time::SystemTime::now()
                 .duration_since(std::time::SystemTime::UNIX_EPOCH)
                 .unwrap()
                 .as_nanos() as u64,
+        )
+    }
+
+    pub const fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            initial_seed: seed,
         }
     }
 
+    /// the seed it was made with, to reproduce a run with `--seed`
+    pub const fn initial_seed(&self) -> u64 {
+        self.initial_seed
+    }
+
     pub fn next(&mut self, bits: u8) -> u32 {
         debug_assert!((1..=32).contains(&bits));
         self.seed = (self.seed.wrapping_mul(0x5DEECE66D).wrapping_add(0xB)) & ((1 << 48) - 1);
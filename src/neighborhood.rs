@@ -0,0 +1,129 @@
+/// which cells a filled cell can grow into, as (row, col) offsets
+/// the offsets may depend on the parity of the row, which the hex grid needs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Neighborhood {
+    /// offsets from cells in even rows and odd rows
+    offsets: [Vec<(i32, i32)>; 2],
+    /// offsets to the cells that have a cell in even rows and odd rows as a neighbor
+    /// these are different from `offsets` when the neighborhood isn't symmetric
+    reverse_offsets: [Vec<(i32, i32)>; 2],
+}
+
+impl Neighborhood {
+    fn from_parity_offsets(offsets: [Vec<(i32, i32)>; 2]) -> Self {
+        assert!(
+            offsets.iter().all(|offsets| !offsets.is_empty()),
+            "neighborhood must not be empty"
+        );
+        assert!(
+            offsets.iter().flatten().all(|&offset| offset != (0, 0)),
+            "a cell must not be its own neighbor"
+        );
+        // cell_1 is a neighbor of cell iff cell_1 = cell + offset for an offset of cell's parity,
+        // so cell = cell_1 - offset where cell's parity is cell_1's parity minus the row offset
+        let reverse_offsets = [0, 1].map(|parity_1: i32| {
+            [0, 1]
+                .into_iter()
+                .flat_map(|parity| {
+                    offsets[parity as usize]
+                        .iter()
+                        .filter(move |(row_offset, _)| (parity_1 - row_offset - parity) % 2 == 0)
+                        .map(|&(row_offset, col_offset)| (-row_offset, -col_offset))
+                })
+                .collect()
+        });
+        Self {
+            offsets,
+            reverse_offsets,
+        }
+    }
+
+    /// the same offsets for every cell
+    pub fn custom(offsets: Vec<(i32, i32)>) -> Self {
+        Self::from_parity_offsets([offsets.clone(), offsets])
+    }
+
+    /// cells within the given manhattan distance, radius 1 is the 4 orthogonal neighbors
+    pub fn von_neumann(radius: i32) -> Self {
+        Self::custom(
+            Self::square_within(radius)
+                .filter(|(row_offset, col_offset)| row_offset.abs() + col_offset.abs() <= radius)
+                .collect(),
+        )
+    }
+
+    /// cells within the given chebyshev distance, radius 1 is the 8 surrounding cells
+    pub fn moore(radius: i32) -> Self {
+        Self::custom(Self::square_within(radius).collect())
+    }
+
+    /// the 8 cells a knight's move away
+    pub fn knight() -> Self {
+        Self::custom(vec![
+            (1, 2),
+            (2, 1),
+            (2, -1),
+            (1, -2),
+            (-1, -2),
+            (-2, -1),
+            (-2, 1),
+            (-1, 2),
+        ])
+    }
+
+    /// the 6 neighbors on a grid of pointy-top hexagons in "odd-r" offset coordinates,
+    /// where odd rows are shifted right by half a cell
+    pub fn hex() -> Self {
+        Self::from_parity_offsets([
+            vec![(0, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0)],
+            vec![(0, 1), (1, 1), (1, 0), (0, -1), (-1, 0), (-1, 1)],
+        ])
+    }
+
+    /// all offsets in the square of the given radius, except (0, 0)
+    fn square_within(radius: i32) -> impl Iterator<Item = (i32, i32)> {
+        assert!(radius >= 1, "radius must be positive");
+        (-radius..=radius)
+            .flat_map(move |row_offset| {
+                (-radius..=radius).map(move |col_offset| (row_offset, col_offset))
+            })
+            .filter(|&offset| offset != (0, 0))
+    }
+
+    /// offsets from a cell in the given row to its neighbors
+    pub fn offsets(&self, row: usize) -> &[(i32, i32)] {
+        &self.offsets[row & 1]
+    }
+
+    /// the neighbors of (row, col) that are in a square of the given size
+    pub fn neighbors(
+        &self,
+        size: usize,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(row)
+            .iter()
+            .filter_map(move |&offset| neighbor(size, row, col, offset))
+    }
+
+    /// the cells in a square of the given size that have (row, col) as a neighbor,
+    /// so the cells whose border status can change when (row, col) changes
+    pub fn reverse_neighbors(
+        &self,
+        size: usize,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.reverse_offsets[row & 1]
+            .iter()
+            .filter_map(move |&offset| neighbor(size, row, col, offset))
+    }
+}
+
+/// the cell at the offset from (row, col), if it's in a square of the given size
+pub fn neighbor(size: usize, row: usize, col: usize, offset: (i32, i32)) -> Option<(usize, usize)> {
+    let row_1 = row.checked_add_signed(offset.0 as isize)?;
+    let col_1 = col.checked_add_signed(offset.1 as isize)?;
+    (row_1 < size && col_1 < size).then_some((row_1, col_1))
+}
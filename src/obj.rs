@@ -0,0 +1,78 @@
+use crate::dish::Dish;
+use crate::topology::Graph;
+
+/// the vertices and faces of a wavefront obj mesh, everything else is ignored
+#[derive(Clone, Debug)]
+pub struct Obj {
+    positions: Vec<[f32; 3]>,
+    /// zero-indexed
+    faces: Vec<Vec<usize>>,
+}
+
+impl Obj {
+    pub fn load(path: &std::path::Path) -> Self {
+        let text = std::fs::read_to_string(path).unwrap();
+        let mut positions = Vec::new();
+        let mut faces = Vec::new();
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => {
+                    let mut position = [0.0; 3];
+                    for coordinate in &mut position {
+                        *coordinate = words.next().unwrap().parse().unwrap();
+                    }
+                    positions.push(position);
+                }
+                Some("f") => faces.push(
+                    words
+                        .map(|word| {
+                            // faces look like `f 1 2 3` or `f 1/1/1 2/2/2 3/3/3`,
+                            // and negative indices count back from the latest vertex
+                            let index: isize = word.split('/').next().unwrap().parse().unwrap();
+                            if index < 0 {
+                                positions.len().checked_add_signed(index).unwrap()
+                            } else {
+                                index as usize - 1
+                            }
+                        })
+                        .collect(),
+                ),
+                _ => {}
+            }
+        }
+        Self { positions, faces }
+    }
+
+    /// the vertices of the mesh connected along the edges of the faces
+    pub fn graph(&self) -> Graph {
+        Graph::from_faces(self.positions.len(), &self.faces)
+    }
+
+    /// saves the mesh with the states of the dish as vertex colors
+    pub fn save_with_colors(&self, path: &std::path::Path, dish: &Dish<Graph>) {
+        use std::fmt::Write;
+
+        assert_eq!(self.positions.len(), dish.states().len());
+        let mut text = String::new();
+        for ([x, y, z], state) in self.positions.iter().zip(dish.states()) {
+            let color = state.to_color();
+            writeln!(
+                text,
+                "v {x} {y} {z} {} {} {}",
+                color.r as f32 / 255.0,
+                color.g as f32 / 255.0,
+                color.b as f32 / 255.0
+            )
+            .unwrap();
+        }
+        for face in &self.faces {
+            text.push('f');
+            for vertex in face {
+                write!(text, " {}", vertex + 1).unwrap();
+            }
+            text.push('\n');
+        }
+        std::fs::write(path, text).unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender};
+use std::thread::JoinHandle;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+
+use crate::dish::Dish;
+use crate::grid::{Grid, Tiling};
+use crate::topology::Topology;
+
+/// frames per second of the recordings
+pub const FRAME_RATE: u16 = 30;
+/// from 1 to 30, where 1 quantizes the colors best but takes most of a second for a big frame
+const GIF_SPEED: i32 = 10;
+/// how many frames can wait to be encoded before recording waits for the encoder
+const GIF_QUEUE_LEN: usize = FRAME_RATE as usize;
+
+/// when frames are recorded
+#[derive(Clone, Copy, Debug)]
+pub enum Pacing {
+    /// every this many steps, so frames get further apart in radius as the dish grows
+    Steps(usize),
+    /// every time the radius (in units of cells) grows by this much, so the animation grows at a constant speed
+    Radius(f32),
+}
+
+impl Pacing {
+    /// parses `<steps>` or `radius:<radius>`
+    pub fn parse(arg: &str) -> Self {
+        arg.strip_prefix("radius:").map_or_else(
+            || Self::Steps(arg.parse().expect("--frames needs a number of steps")),
+            |radius| Self::Radius(radius.parse().expect("--frames radius needs a radius")),
+        )
+    }
+}
+
+/// where the frames go
+enum Output {
+    /// encoding a gif frame is slow, so it's done on another thread
+    Gif {
+        frames: SyncSender<RgbaImage>,
+        encoder: JoinHandle<()>,
+    },
+    /// apng needs the frame count before the frames,
+    /// so they're written to numbered pngs in the temporary directory and put together when it's finished
+    Apng { path: PathBuf, frames: PathBuf },
+    /// numbered png files in a directory
+    Pngs(PathBuf),
+}
+
+/// records the growth of a square dish as an animation, with a pixel for each cell
+pub struct Recorder {
+    output: Output,
+    pacing: Pacing,
+    /// the steps or radius since the last frame
+    since_frame: f32,
+    frame_count: usize,
+}
+
+impl Recorder {
+    /// the format is from the extension, a gif for `.gif`, an apng for `.png`,
+    /// or numbered pngs in a directory for anything else
+    pub fn new(path: &Path, pacing: Pacing) -> Self {
+        let output = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("gif") => {
+                let file = BufWriter::new(File::create(path).unwrap());
+                let (frames, receiver) = mpsc::sync_channel::<RgbaImage>(GIF_QUEUE_LEN);
+                let encoder = std::thread::spawn(move || {
+                    let mut encoder = GifEncoder::new_with_speed(file, GIF_SPEED);
+                    encoder.set_repeat(Repeat::Infinite).unwrap();
+                    for image in receiver {
+                        encoder
+                            .encode_frame(Frame::from_parts(
+                                image,
+                                0,
+                                0,
+                                Delay::from_numer_denom_ms(1000, FRAME_RATE.into()),
+                            ))
+                            .unwrap();
+                    }
+                });
+                Output::Gif { frames, encoder }
+            }
+            Some("png") => {
+                let frames =
+                    std::env::temp_dir().join(format!("spore_frames_{}", std::process::id()));
+                std::fs::create_dir_all(&frames).unwrap();
+                Output::Apng {
+                    path: path.to_path_buf(),
+                    frames,
+                }
+            }
+            _ => {
+                std::fs::create_dir_all(path).unwrap();
+                Output::Pngs(path.to_path_buf())
+            }
+        };
+        Self {
+            output,
+            pacing,
+            since_frame: 0.0,
+            frame_count: 0,
+        }
+    }
+
+    pub const fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// call after each step the dish takes, records a frame when it's due
+    pub fn step(&mut self, dish: &Dish<Grid>) {
+        // from the growth model in the window, d/dt (area) = perimeter * d/dt (radius), and each step is one cell of area
+        let (since_frame, spacing) = match self.pacing {
+            Pacing::Steps(steps) => (1.0, steps as f32),
+            Pacing::Radius(radius) => (1.0 / dish.perimeter().max(1.0), radius),
+        };
+        self.since_frame += since_frame;
+        if self.since_frame >= spacing {
+            self.since_frame -= spacing;
+            self.record_frame(dish);
+        }
+    }
+
+    pub fn record_frame(&mut self, dish: &Dish<Grid>) {
+        let grid = dish.topology();
+        assert_eq!(
+            grid.tiling(),
+            Tiling::Square,
+            "only square dishes can be recorded"
+        );
+        let mut image = RgbaImage::new(grid.size() as u32, grid.size() as u32);
+        for cell in 0..grid.cell_count() {
+            let (row, col) = grid.row_col(cell);
+            let color = dish.states()[cell].to_color();
+            image.put_pixel(
+                col as u32,
+                row as u32,
+                image::Rgba([color.r, color.g, color.b, color.a]),
+            );
+        }
+        match &mut self.output {
+            Output::Gif { frames, .. } => frames.send(image).unwrap(),
+            Output::Apng {
+                frames: directory, ..
+            }
+            | Output::Pngs(directory) => {
+                image.save(frame_path(directory, self.frame_count)).unwrap();
+            }
+        }
+        self.frame_count += 1;
+    }
+
+    /// records the last frame and finishes writing the file
+    pub fn finish(mut self, dish: &Dish<Grid>) {
+        if self.since_frame > 0.0 || self.frame_count == 0 {
+            self.record_frame(dish);
+        }
+        match self.output {
+            Output::Gif { frames, encoder } => {
+                // the encoder finishes the file once there are no more frames
+                drop(frames);
+                encoder.join().unwrap();
+            }
+            Output::Apng { path, frames } => {
+                let grid = dish.topology();
+                let mut encoder = png::Encoder::new(
+                    BufWriter::new(File::create(path).unwrap()),
+                    grid.size() as u32,
+                    grid.size() as u32,
+                );
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(self.frame_count as u32, 0).unwrap();
+                encoder.set_frame_delay(1, FRAME_RATE).unwrap();
+                let mut writer = encoder.write_header().unwrap();
+                for frame in 0..self.frame_count {
+                    let image = image::open(frame_path(&frames, frame))
+                        .unwrap()
+                        .into_rgba8();
+                    writer.write_image_data(&image).unwrap();
+                }
+                writer.finish().unwrap();
+                std::fs::remove_dir_all(frames).unwrap();
+            }
+            Output::Pngs(_) => {}
+        }
+    }
+}
+
+fn frame_path(directory: &Path, frame: usize) -> PathBuf {
+    directory.join(format!("frame_{frame:05}.png"))
+}
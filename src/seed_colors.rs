@@ -0,0 +1,144 @@
+use crate::grid::Grid;
+use crate::my_rng::Rng;
+use crate::state::State;
+
+type Color = (u8, u8, u8);
+
+/// named palettes for `SeedColors::List`
+pub const PALETTES: [(&str, &[Color]); 4] = [
+    (
+        "pastel",
+        &[
+            (244, 172, 183),
+            (255, 217, 163),
+            (196, 232, 194),
+            (168, 216, 234),
+            (203, 182, 232),
+        ],
+    ),
+    (
+        "earth",
+        &[
+            (121, 85, 61),
+            (166, 124, 82),
+            (110, 130, 76),
+            (196, 164, 110),
+            (87, 98, 89),
+        ],
+    ),
+    (
+        "neon",
+        &[
+            (255, 56, 100),
+            (255, 214, 0),
+            (57, 255, 20),
+            (0, 229, 255),
+            (190, 70, 255),
+        ],
+    ),
+    (
+        "ocean",
+        &[
+            (3, 57, 108),
+            (0, 91, 150),
+            (100, 151, 177),
+            (179, 205, 224),
+            (0, 128, 128),
+        ],
+    ),
+];
+
+/// what color the initial seeds are
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SeedColors {
+    /// each channel uniformly random in 50..200
+    Random,
+    /// evenly spaced hues around the color wheel
+    Hues,
+    /// cycles through the colors, one per seed
+    List(Vec<Color>),
+    /// the pixel of the image at the seed, with the image stretched over the grid
+    Image(image::RgbaImage),
+}
+
+impl SeedColors {
+    /// random, hues, and the named palettes, in the order they're cycled through in the window
+    pub fn options() -> Vec<Self> {
+        [Self::Random, Self::Hues]
+            .into_iter()
+            .chain(
+                PALETTES
+                    .iter()
+                    .map(|(_, palette)| Self::List(palette.to_vec())),
+            )
+            .collect()
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Random => "random",
+            Self::Hues => "hues",
+            Self::List(colors) => PALETTES
+                .iter()
+                .find(|(_, palette)| palette == colors)
+                .map_or("list", |(name, _)| name),
+            Self::Image(_) => "image",
+        }
+    }
+
+    /// parses colors like `#ff8000` or `ff8000`
+    pub fn parse_list(arg: &str) -> Self {
+        Self::List(
+            arg.split(',')
+                .map(|color| {
+                    let color = u32::from_str_radix(color.trim().trim_start_matches('#'), 16)
+                        .unwrap_or_else(|_| panic!("invalid color {color}"));
+                    ((color >> 16) as u8, (color >> 8) as u8, color as u8)
+                })
+                .collect(),
+        )
+    }
+
+    /// the colors of seeds at the cells
+    pub fn colors(&self, rng: &mut Rng, grid: &Grid, cells: &[usize]) -> Vec<State> {
+        match self {
+            Self::Random => cells.iter().map(|_| State::random_filled(rng)).collect(),
+            Self::Hues => {
+                // start at a random hue so resets look different
+                let offset = rng.next_u32_n(360) as f32 / 360.0;
+                (0..cells.len())
+                    .map(|i| {
+                        hsv_to_state((i as f32 / cells.len() as f32 + offset).fract(), 0.6, 0.8)
+                    })
+                    .collect()
+            }
+            Self::List(colors) => (0..cells.len())
+                .map(|i| {
+                    let (r, g, b) = colors[i % colors.len()];
+                    State::Filled { r, g, b }
+                })
+                .collect(),
+            Self::Image(image) => cells
+                .iter()
+                .map(|&cell| {
+                    let [r, g, b, _] = grid.sample_image_at(image, cell).0;
+                    State::Filled { r, g, b }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// hue, saturation, and value in 0..1
+fn hsv_to_state(hue: f32, saturation: f32, value: f32) -> State {
+    let channel = |n: f32| {
+        let k = hue.mul_add(6.0, n) % 6.0;
+        let val = (value * saturation).mul_add(-k.min(4.0 - k).clamp(0.0, 1.0), value);
+        (val * 255.0).round() as u8
+    };
+    State::Filled {
+        r: channel(5.0),
+        g: channel(3.0),
+        b: channel(1.0),
+    }
+}
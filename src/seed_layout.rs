@@ -0,0 +1,209 @@
+use crate::grid::Grid;
+use crate::my_rng::Rng;
+use crate::state::State;
+
+/// where the initial seeds go
+#[derive(Clone, Debug, PartialEq)]
+pub enum SeedLayout {
+    /// uniformly random cells, which can repeat
+    Uniform,
+    /// uniformly random cells without repeats
+    Distinct,
+    /// random cells spread out so no two are close together
+    PoissonDisk,
+    /// a regular grid of cells
+    Grid,
+    /// evenly spaced around a centered circle
+    Ring,
+    /// evenly spaced along the bottom edge
+    Edge,
+    /// a single seed in the center, ignoring the seed count
+    Center,
+    Points(Vec<(f32, f32)>),
+}
+
+impl SeedLayout {
+    /// all layouts that don't need extra data, in the order they're cycled through in the window
+    pub const ALL: [Self; 7] = [
+        Self::Uniform,
+        Self::Distinct,
+        Self::PoissonDisk,
+        Self::Grid,
+        Self::Ring,
+        Self::Edge,
+        Self::Center,
+    ];
+
+    pub const fn name(&self) -> &str {
+        match self {
+            Self::Uniform => "uniform",
+            Self::Distinct => "distinct",
+            Self::PoissonDisk => "poisson",
+            Self::Grid => "grid",
+            Self::Ring => "ring",
+            Self::Edge => "edge",
+            Self::Center => "center",
+            Self::Points(_) => "points",
+        }
+    }
+
+    /// loads points from a csv file with a point like `0.5,0.25` on each line,
+    /// or a json file with an array of points like `[[0.5, 0.25], [0.1, 0.9]]`
+    pub fn load_points(path: &std::path::Path) -> Self {
+        let text = std::fs::read_to_string(path).unwrap();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            return Self::Points(parse_json_points(&text));
+        }
+        let coordinates = text
+            .lines()
+            .flat_map(|line| line.split(','))
+            // skips headers
+            .filter_map(|word| word.trim().parse().ok())
+            .collect::<Vec<f32>>();
+        assert!(
+            coordinates.len() % 2 == 0,
+            "points must have an x and y coordinate"
+        );
+        Self::Points(
+            coordinates
+                .chunks_exact(2)
+                .map(|point| (point[0], point[1]))
+                .collect(),
+        )
+    }
+
+    /// the cells to seed, only ones that can be filled
+    pub fn cells(
+        &self,
+        rng: &mut Rng,
+        grid: &Grid,
+        states: &[State],
+        seed_count: usize,
+    ) -> Vec<usize> {
+        let seedable = |cell: usize| matches!(states[cell], State::Empty | State::Filled { .. });
+        let seedable_cells = (0..states.len())
+            .filter(|&cell| seedable(cell))
+            .collect::<Vec<_>>();
+        if seedable_cells.is_empty() {
+            return Vec::new();
+        }
+        let (width, height) = grid.extent();
+        let cell_at = |(x, y): (f32, f32)| grid.unit_cell_at(x, y).filter(|&cell| seedable(cell));
+        match self {
+            Self::Uniform => (0..seed_count)
+                .map(|_| seedable_cells[rng.next_u32_n(seedable_cells.len() as u32) as usize])
+                .collect(),
+            Self::Distinct => {
+                // partial fisher-yates shuffle
+                let mut cells = seedable_cells;
+                let seed_count = seed_count.min(cells.len());
+                for i in 0..seed_count {
+                    let j = i + rng.next_u32_n((cells.len() - i) as u32) as usize;
+                    cells.swap(i, j);
+                }
+                cells.truncate(seed_count);
+                cells
+            }
+            Self::PoissonDisk => {
+                // mitchell's best candidate: pick the candidate farthest from the existing seeds
+                const CANDIDATE_COUNT: usize = 10;
+                let mut cells: Vec<usize> = Vec::with_capacity(seed_count);
+                for _ in 0..seed_count {
+                    let distance_to_seeds = |cell: usize| {
+                        let (x, y) = grid.center(cell);
+                        cells
+                            .iter()
+                            .map(|&cell_1| {
+                                let (x_1, y_1) = grid.center(cell_1);
+                                (x_1 - x).hypot(y_1 - y)
+                            })
+                            .fold(f32::INFINITY, f32::min)
+                    };
+                    let best = (0..CANDIDATE_COUNT)
+                        .map(|_| {
+                            seedable_cells[rng.next_u32_n(seedable_cells.len() as u32) as usize]
+                        })
+                        .max_by(|&cell, &cell_1| {
+                            distance_to_seeds(cell).total_cmp(&distance_to_seeds(cell_1))
+                        })
+                        .unwrap();
+                    cells.push(best);
+                }
+                cells
+            }
+            Self::Grid => {
+                let cols = (seed_count as f32 * width / height).sqrt().ceil().max(1.0) as usize;
+                let rows = seed_count.div_ceil(cols);
+                (0..seed_count)
+                    .filter_map(|i| {
+                        cell_at((
+                            ((i % cols) as f32 + 0.5) / cols as f32,
+                            ((i / cols) as f32 + 0.5) / rows as f32,
+                        ))
+                    })
+                    .collect()
+            }
+            Self::Ring => (0..seed_count)
+                .filter_map(|i| {
+                    let angle = i as f32 / seed_count as f32 * std::f32::consts::TAU;
+                    cell_at((
+                        0.35f32.mul_add(angle.cos(), 0.5),
+                        0.35f32.mul_add(angle.sin(), 0.5),
+                    ))
+                })
+                .collect(),
+            Self::Edge => (0..seed_count)
+                .filter_map(|i| cell_at(((i as f32 + 0.5) / seed_count as f32, 1.0 - 0.5 / height)))
+                .collect(),
+            Self::Center => cell_at((0.5, 0.5)).into_iter().collect(),
+            Self::Points(points) => points.iter().copied().filter_map(cell_at).collect(),
+        }
+    }
+}
+
+/// parses a json array of points like `[[0.5, 0.25], [0.1, 0.9]]`
+fn parse_json_points(text: &str) -> Vec<(f32, f32)> {
+    let Some(points) = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|points| points.strip_suffix(']'))
+    else {
+        invalid_json_points()
+    };
+    // each point but the first starts with a comma, and they all end with a `]`
+    let mut points = points.split(']').collect::<Vec<_>>();
+    if points.pop().is_some_and(|rest| !rest.trim().is_empty()) {
+        invalid_json_points();
+    }
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let point = point.trim();
+            let point = if i == 0 {
+                Some(point)
+            } else {
+                point.strip_prefix(',')
+            };
+            let coordinates = point
+                .and_then(|point| point.trim().strip_prefix('['))
+                .map(|point| {
+                    point
+                        .split(',')
+                        .map(|coordinate| coordinate.trim().parse::<f32>())
+                        .collect::<Result<Vec<_>, _>>()
+                });
+            match coordinates {
+                Some(Ok(coordinates)) if coordinates.len() == 2 => (coordinates[0], coordinates[1]),
+                _ => invalid_json_points(),
+            }
+        })
+        .collect()
+}
+
+fn invalid_json_points() -> ! {
+    panic!("a json seed layout must be an array of points like [[0.5, 0.25], [0.1, 0.9]]");
+}
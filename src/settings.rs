@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use crate::brush::BrushColor;
+use crate::keymap::Keymap;
+use crate::learned_mutation::LearnedMutation;
+use crate::recorder::Pacing;
+use crate::seed_colors::SeedColors;
+use crate::seed_layout::SeedLayout;
+use crate::state::State;
+
+/// what's given on the command line and stays the same while the window is open
+#[allow(clippy::struct_excessive_bools)]
+pub struct Options {
+    /// the states before seeding, like obstacles and cells outside the domain
+    pub initial_states: Vec<State>,
+    pub seed_layout: SeedLayout,
+    pub seed_colors: SeedColors,
+    /// the color new cells are pulled toward at each cell
+    pub target: Option<Vec<(u8, u8, u8)>>,
+    pub target_strength: f32,
+    pub learned_mutation: Option<LearnedMutation>,
+    pub mutation_strengths: Option<Vec<f32>>,
+    pub direction_weights: Option<Vec<f32>>,
+    /// how many cells are seeded initially
+    pub seed_count: usize,
+    pub keymap: Keymap,
+    pub record_path: PathBuf,
+    /// how frames are recorded from the start, or none to only record with the key
+    pub frames: Option<Pacing>,
+    /// whether to record where each cell was filled from
+    pub lineage: bool,
+    /// whether to record every change so it can be replayed
+    pub history: bool,
+}
+
+/// what can be changed while the window is open
+#[derive(Clone, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Settings {
+    pub paused: bool,
+    /// how much the radius (in units of cells) of the colored region should grow per second
+    pub radius_per_second: f32,
+    /// how many cells are seeded when the dish is reset
+    pub seed_count: usize,
+    pub color_step: i32,
+    pub highlight_border: bool,
+    pub seed_layout: SeedLayout,
+    pub seed_colors: SeedColors,
+    pub brush_color: BrushColor,
+    /// the last color picked from the dish
+    pub picked_color: (u8, u8, u8),
+    /// how strongly new cells are pulled toward the target, or none if there's no target
+    pub target_strength: Option<f32>,
+    /// whether to mutate by the learned mutation, or none if there isn't one
+    pub use_learned_mutation: Option<bool>,
+    pub show_panel: bool,
+    /// whether to show the parameters and stats in the top left
+    pub show_hud: bool,
+    /// whether to show what's known about the cell under the mouse
+    pub show_inspector: bool,
+    /// whether to show the keymap
+    pub show_help: bool,
+}
+
+/// the option after the current one, or the first if the current one isn't an option
+pub fn next<T: PartialEq + Clone>(options: &[T], current: &T) -> T {
+    let index = options
+        .iter()
+        .position(|option| option == current)
+        .map_or(0, |index| (index + 1) % options.len());
+    options[index].clone()
+}
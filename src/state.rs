@@ -3,7 +3,15 @@ use crate::my_rng::Rng;
 #[derive(Clone, Copy, Debug)]
 pub enum State {
     Empty,
-    Filled { r: u8, g: u8, b: u8 },
+    Filled {
+        r: u8,
+        g: u8,
+        b: u8,
+    },
+    /// can never be filled
+    Obstacle,
+    /// outside the domain of the dish, so it doesn't exist
+    Outside,
 }
 
 impl State {
@@ -22,7 +30,7 @@ impl State {
 
     pub fn rand_step(self, rng: &mut Rng, color_step: i32) -> Self {
         match self {
-            Self::Empty => panic!("state must not be empty"),
+            Self::Empty | Self::Obstacle | Self::Outside => panic!("state must be filled"),
             Self::Filled { r, g, b } => Self::Filled {
                 r: Self::rand_step_single(rng, color_step, r),
                 g: Self::rand_step_single(rng, color_step, g),
@@ -31,10 +39,61 @@ impl State {
         }
     }
 
+    /// adds the difference to each channel
+    pub fn shift(self, (dr, dg, db): (i16, i16, i16)) -> Self {
+        match self {
+            Self::Empty | Self::Obstacle | Self::Outside => panic!("state must be filled"),
+            Self::Filled { r, g, b } => Self::Filled {
+                r: (r as i16 + dr).clamp(0, 255) as u8,
+                g: (g as i16 + dg).clamp(0, 255) as u8,
+                b: (b as i16 + db).clamp(0, 255) as u8,
+            },
+        }
+    }
+
+    /// moves `strength` (from 0 to 1) of the way toward the target color
+    pub fn pull_toward(self, rng: &mut Rng, target: (u8, u8, u8), strength: f32) -> Self {
+        let Self::Filled { r, g, b } = self else {
+            panic!("state must be filled")
+        };
+        // rounds randomly so small pulls still move the color on average
+        let mut toward = |val: u8, target_val: u8| {
+            let val = strength.mul_add(target_val as f32 - val as f32, val as f32);
+            (val + rng.next_u32_n(1024) as f32 / 1024.0).floor() as u8
+        };
+        Self::Filled {
+            r: toward(r, target.0),
+            g: toward(g, target.1),
+            b: toward(b, target.2),
+        }
+    }
+
+    /// for saving dishes, where filled states are opaque, obstacles are half transparent,
+    /// and empty states and states outside the domain are transparent black and white
+    pub const fn to_rgba(self) -> [u8; 4] {
+        match self {
+            Self::Empty => [0, 0, 0, 0],
+            Self::Filled { r, g, b } => [r, g, b, 255],
+            Self::Obstacle => [0, 0, 0, 128],
+            Self::Outside => [255, 255, 255, 0],
+        }
+    }
+
+    pub const fn from_rgba([r, g, b, a]: [u8; 4]) -> Self {
+        match a {
+            192.. => Self::Filled { r, g, b },
+            64.. => Self::Obstacle,
+            _ if r >= 128 => Self::Outside,
+            _ => Self::Empty,
+        }
+    }
+
     pub const fn to_color(self) -> raylib::color::Color {
         match self {
             Self::Empty => raylib::color::Color::new(0, 0, 0, 255),
             Self::Filled { r, g, b } => raylib::color::Color::new(r, g, b, 255),
+            Self::Obstacle => raylib::color::Color::new(32, 32, 32, 255),
+            Self::Outside => raylib::color::Color::new(0, 0, 0, 0),
         }
     }
 }
@@ -0,0 +1,77 @@
+/// how the cells of a dish are connected
+/// cells are numbered `0..cell_count()`, and each cell has up to `direction_count()` neighbors
+pub trait Topology {
+    fn cell_count(&self) -> usize;
+
+    /// the most neighbors any cell can have
+    fn direction_count(&self) -> usize;
+
+    /// the neighbor of the cell in the direction, if there is one
+    /// `direction` is in `0..direction_count()`
+    fn neighbor(&self, cell: usize, direction: usize) -> Option<usize>;
+
+    fn neighbors(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.direction_count()).filter_map(move |direction| self.neighbor(cell, direction))
+    }
+
+    /// the cells that have the cell as a neighbor,
+    /// so the cells whose border status can change when the cell changes
+    /// must be overridden if the topology isn't symmetric
+    fn reverse_neighbors(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbors(cell)
+    }
+}
+
+/// an arbitrary undirected graph, like the vertices of a mesh or the cells of a voronoi diagram
+#[derive(Clone, Debug)]
+pub struct Graph {
+    neighbors: Vec<Vec<usize>>,
+    direction_count: usize,
+}
+
+impl Graph {
+    pub fn from_edges(cell_count: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut neighbors = vec![Vec::new(); cell_count];
+        for (cell, cell_1) in edges {
+            if cell != cell_1 && !neighbors[cell].contains(&cell_1) {
+                neighbors[cell].push(cell_1);
+                neighbors[cell_1].push(cell);
+            }
+        }
+        let direction_count = neighbors.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        Self {
+            neighbors,
+            direction_count,
+        }
+    }
+
+    /// connects consecutive vertices of each face, like the edges of a polygon mesh
+    pub fn from_faces(vertex_count: usize, faces: &[Vec<usize>]) -> Self {
+        Self::from_edges(
+            vertex_count,
+            faces.iter().flat_map(|face| {
+                face.iter()
+                    .copied()
+                    .zip(face.iter().copied().cycle().skip(1))
+            }),
+        )
+    }
+}
+
+impl Topology for Graph {
+    fn cell_count(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    fn direction_count(&self) -> usize {
+        self.direction_count
+    }
+
+    fn neighbor(&self, cell: usize, direction: usize) -> Option<usize> {
+        self.neighbors[cell].get(direction).copied()
+    }
+
+    fn neighbors(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbors[cell].iter().copied()
+    }
+}
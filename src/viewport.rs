@@ -0,0 +1,71 @@
+use crate::grid::Grid;
+
+/// how much the mouse wheel zooms per notch
+const ZOOM_PER_NOTCH: f32 = 1.1;
+
+/// where the grid is drawn in the window, mapping points in the units of `Grid::extent` to pixels
+#[derive(Clone, Copy, Debug)]
+pub struct Viewport {
+    /// pixels per unit
+    scale: f32,
+    /// the pixel where the origin of the grid is drawn
+    offset: (f32, f32),
+}
+
+impl Viewport {
+    /// the whole grid, centered in the window
+    pub fn fit(grid: &Grid, width: f32, height: f32) -> Self {
+        let scale = grid.scale_to_fit(width, height);
+        Self::centered(grid, width, height, scale)
+    }
+
+    /// one pixel per cell, centered in the window
+    pub fn one_to_one(grid: &Grid, width: f32, height: f32) -> Self {
+        Self::centered(grid, width, height, grid.unit_scale())
+    }
+
+    fn centered(grid: &Grid, width: f32, height: f32, scale: f32) -> Self {
+        let (extent_width, extent_height) = grid.extent();
+        Self {
+            scale,
+            offset: (
+                extent_width.mul_add(-scale, width) / 2.0,
+                extent_height.mul_add(-scale, height) / 2.0,
+            ),
+        }
+    }
+
+    pub const fn scale(self) -> f32 {
+        self.scale
+    }
+
+    pub const fn to_screen(self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x.mul_add(self.scale, self.offset.0),
+            y.mul_add(self.scale, self.offset.1),
+        )
+    }
+
+    pub fn to_grid(self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.offset.0) / self.scale,
+            (y - self.offset.1) / self.scale,
+        )
+    }
+
+    /// zooms in by the number of mouse wheel notches, keeping the point under the pixel in place
+    pub fn zoom(&mut self, notches: f32, x: f32, y: f32) {
+        let (grid_x, grid_y) = self.to_grid(x, y);
+        self.scale *= ZOOM_PER_NOTCH.powf(notches);
+        self.offset = (
+            grid_x.mul_add(-self.scale, x),
+            grid_y.mul_add(-self.scale, y),
+        );
+    }
+
+    /// moves the grid by the number of pixels
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.offset.0 += dx;
+        self.offset.1 += dy;
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use crate::dish::Dish;
+use crate::state::State;
+use crate::topology::Topology;
+
+/// the most voxels a vox model can have in each dimension
+pub const MAX_VOX_SIZE: usize = 256;
+
+/// a box of voxels, where cell `(z * height + y) * width + x` is at (x, y, z)
+#[derive(Clone, Copy, Debug)]
+pub struct VoxelGrid {
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl VoxelGrid {
+    pub const fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub const fn cell(&self, x: usize, y: usize, z: usize) -> usize {
+        (z * self.height + y) * self.width + x
+    }
+
+    pub const fn xyz(&self, cell: usize) -> (usize, usize, usize) {
+        (
+            cell % self.width,
+            cell / self.width % self.height,
+            cell / (self.width * self.height),
+        )
+    }
+}
+
+impl Topology for VoxelGrid {
+    fn cell_count(&self) -> usize {
+        self.width * self.height * self.depth
+    }
+
+    fn direction_count(&self) -> usize {
+        6
+    }
+
+    fn neighbor(&self, cell: usize, direction: usize) -> Option<usize> {
+        let (x, y, z) = self.xyz(cell);
+        let (x_1, y_1, z_1) = match direction {
+            0 => (x + 1, y, z),
+            1 => (x, y + 1, z),
+            2 => (x, y, z + 1),
+            3 => (x.checked_sub(1)?, y, z),
+            4 => (x, y.checked_sub(1)?, z),
+            5 => (x, y, z.checked_sub(1)?),
+            _ => unreachable!("direction out of range"),
+        };
+        (x_1 < self.width && y_1 < self.height && z_1 < self.depth)
+            .then(|| self.cell(x_1, y_1, z_1))
+    }
+}
+
+impl Dish<VoxelGrid> {
+    /// saves a png for each z, named by z
+    pub fn save_slices(&self, dir: &std::path::Path) {
+        let grid = self.topology();
+        std::fs::create_dir_all(dir).unwrap();
+        for z in 0..grid.depth {
+            let image =
+                image::ImageBuffer::from_fn(grid.width as u32, grid.height as u32, |x, y| {
+                    let color = self.states()[grid.cell(x as usize, y as usize, z)].to_color();
+                    image::Rgb([color.r, color.g, color.b])
+                });
+            image.save(dir.join(format!("{z:04}.png"))).unwrap();
+        }
+    }
+
+    /// saves in the magicavoxel format, with z up
+    /// the format only has 255 colors, so if there are more they're quantized
+    pub fn save_to_vox(&self, path: &std::path::Path) {
+        let grid = self.topology();
+        assert!(
+            grid.width <= MAX_VOX_SIZE && grid.height <= MAX_VOX_SIZE && grid.depth <= MAX_VOX_SIZE,
+            "vox models can be at most {MAX_VOX_SIZE} voxels in each dimension"
+        );
+
+        // palette index 0 means empty, so colors get indices 1..=255
+        let mut palette: HashMap<(u8, u8, u8), u8, ahash::RandomState> = HashMap::default();
+        for state in self.states() {
+            if let State::Filled { r, g, b } = *state {
+                let len = palette.len();
+                if len < 255 {
+                    palette.entry((r, g, b)).or_insert(len as u8 + 1);
+                } else if !palette.contains_key(&(r, g, b)) {
+                    palette.clear();
+                    break;
+                }
+            }
+        }
+        let quantize = palette.is_empty();
+        // 6 levels of red and blue and 7 of green give 252 colors
+        let quantized_index = |r: u8, g: u8, b: u8| {
+            let level = |val: u8, levels: u32| (val as u32 * (levels - 1) + 127) / 255;
+            1 + ((level(r, 6) * 7 + level(g, 7)) * 6 + level(b, 6)) as u8
+        };
+
+        let mut voxels = Vec::new();
+        for (cell, state) in self.states().iter().enumerate() {
+            if let State::Filled { r, g, b } = *state {
+                let (x, y, z) = grid.xyz(cell);
+                let index = if quantize {
+                    quantized_index(r, g, b)
+                } else {
+                    palette[&(r, g, b)]
+                };
+                voxels.extend([x as u8, y as u8, z as u8, index]);
+            }
+        }
+
+        let mut rgba = vec![0; 4 * 256];
+        if quantize {
+            for r in 0..6 {
+                for g in 0..7 {
+                    for b in 0..6 {
+                        let index = (r * 7 + g) * 6 + b;
+                        rgba[4 * index..4 * index + 4].copy_from_slice(&[
+                            (r * 255 / 5) as u8,
+                            (g * 255 / 6) as u8,
+                            (b * 255 / 5) as u8,
+                            255,
+                        ]);
+                    }
+                }
+            }
+        } else {
+            for (&(r, g, b), &index) in &palette {
+                let index = index as usize - 1;
+                rgba[4 * index..4 * index + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+
+        let size = [grid.width, grid.height, grid.depth]
+            .into_iter()
+            .flat_map(|len| (len as u32).to_le_bytes())
+            .collect::<Vec<_>>();
+        let xyzi = ((voxels.len() / 4) as u32)
+            .to_le_bytes()
+            .into_iter()
+            .chain(voxels)
+            .collect::<Vec<_>>();
+        let children = [
+            vox_chunk(*b"SIZE", &size),
+            vox_chunk(*b"XYZI", &xyzi),
+            vox_chunk(*b"RGBA", &rgba),
+        ]
+        .concat();
+
+        let mut bytes = Vec::new();
+        bytes.extend(b"VOX ");
+        bytes.extend(150u32.to_le_bytes());
+        bytes.extend(b"MAIN");
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend((children.len() as u32).to_le_bytes());
+        bytes.extend(children);
+        std::fs::write(path, bytes).unwrap();
+    }
+}
+
+/// a chunk without children
+fn vox_chunk(id: [u8; 4], content: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(12 + content.len());
+    bytes.extend(id);
+    bytes.extend((content.len() as u32).to_le_bytes());
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend(content);
+    bytes
+}
@@ -0,0 +1,82 @@
+use crate::grid::Grid;
+use crate::topology::Topology;
+
+/// the least weight of a direction relative to the average, so growth never stops completely
+const MIN_WEIGHT: f32 = 0.1;
+
+/// which directions growth favors
+#[derive(Clone, Debug)]
+pub enum Wind {
+    /// a weight for each direction, in the order of the offsets of the neighborhood
+    Weights(Vec<f32>),
+    /// blows the same way everywhere, with a length up to 1 where growth against it almost stops
+    Vector(f32, f32),
+    /// swirls clockwise around the center
+    Spiral,
+    /// blows the way of the vector at each cell of an image stretched over the grid,
+    /// where red is x and green is y, with 0 at 128
+    Field(image::RgbaImage),
+}
+
+impl Wind {
+    /// the weight of each direction of each cell at `cell * direction_count + direction`,
+    /// with an average of 1 at each cell
+    pub fn weights(&self, grid: &Grid) -> Vec<f32> {
+        let direction_count = grid.direction_count();
+        if let Self::Weights(weights) = self {
+            assert_eq!(
+                weights.len(),
+                direction_count,
+                "there must be a weight for each direction"
+            );
+            assert!(
+                weights.iter().all(|&weight| weight > 0.0),
+                "weights must be positive"
+            );
+        }
+        let field = match self {
+            Self::Field(image) => grid.sample_image(image),
+            _ => Vec::new(),
+        };
+        // the wind at the cell, or none if the weights are given directly
+        let vector = |cell: usize| match self {
+            Self::Weights(_) => None,
+            Self::Vector(x, y) => Some((*x, *y)),
+            Self::Spiral => {
+                let (x, y) = grid.unit_center(cell);
+                let (dx, dy) = (x - 0.5, y - 0.5);
+                let len = dx.hypot(dy).max(f32::EPSILON);
+                // mostly around the center and a bit outward
+                Some((
+                    0.3f32.mul_add(dx, -0.8 * dy) / len,
+                    0.3f32.mul_add(dy, 0.8 * dx) / len,
+                ))
+            }
+            Self::Field(_) => {
+                let [r, g, _, _] = field[cell].0;
+                Some(((r as f32 - 128.0) / 127.0, (g as f32 - 128.0) / 127.0))
+            }
+        };
+        let mut weights = Vec::with_capacity(grid.cell_count() * direction_count);
+        for cell in 0..grid.cell_count() {
+            let vector = vector(cell);
+            let start = weights.len();
+            weights.extend((0..direction_count).map(|direction| {
+                match (grid.direction_vector(cell, direction), vector, self) {
+                    (None, _, _) => 0.0,
+                    (Some(_), None, Self::Weights(weights)) => weights[direction],
+                    (Some((x, y)), Some((wind_x, wind_y)), _) => {
+                        let len = x.hypot(y);
+                        (wind_x * x / len + wind_y * y / len + 1.0).max(MIN_WEIGHT)
+                    }
+                    _ => unreachable!("only weights have no vector"),
+                }
+            }));
+            let mean = weights[start..].iter().sum::<f32>() / direction_count as f32;
+            for weight in &mut weights[start..] {
+                *weight /= mean;
+            }
+        }
+        weights
+    }
+}